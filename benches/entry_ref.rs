@@ -0,0 +1,56 @@
+//! Compares allocations and time of `try_get_or_insert_as` and `entry_ref_as` on a hot loop that
+//! mostly hits existing keys.
+//!
+//! Run with `cargo bench --bench entry_ref`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use dynamic_object::Object;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const KEYS: [&str; 8] = ["a", "b", "c", "d", "e", "f", "g", "h"];
+const ITERATIONS: usize = 1_000_000;
+
+fn measure(name: &str, mut bump: impl FnMut(&mut Object, &str)) {
+    let mut object = Object::new();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        bump(&mut object, black_box(KEYS[i % KEYS.len()]));
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!("{name:<22} {elapsed:>12.2?} {allocations:>10} allocations");
+    black_box(object);
+}
+
+fn main() {
+    measure("try_get_or_insert_as", |object, key| {
+        *object.try_get_or_insert_as(key, 0u64).unwrap().into_mut() += 1;
+    });
+    measure("entry_ref_as", |object, key| {
+        *object.entry_ref_as(key, || 0u64).unwrap().into_mut() += 1;
+    });
+}
@@ -0,0 +1,125 @@
+//! Helpers for arrays of type-erased values.
+
+use crate::AnyType;
+
+/// An array of type-erased values.
+///
+/// This is the representation of arrays used throughout the crate, e.g. by the JSON, TOML and
+/// YAML conversions. Since it is a plain `Vec`, it can't implement `FromIterator` or `Extend` for
+/// unboxed values; [`ArrayExt`] provides the equivalent methods instead.
+pub type Array = Vec<Box<dyn AnyType>>;
+
+/// Typed access to the elements of an [`Array`].
+pub trait ArrayExt {
+    /// Creates an array by boxing every value of `values`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Array, ArrayExt};
+    /// let array = Array::from_values([1, 2, 3]);
+    /// assert_eq!(array.len(), 3);
+    /// ```
+    fn from_values<V: AnyType>(values: impl IntoIterator<Item = V>) -> Self;
+
+    /// Boxes and appends every value of `values`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Array, ArrayExt};
+    /// let mut array = Array::from_values([1]);
+    /// array.extend_values(["two", "three"]);
+    /// assert_eq!(array.len(), 3);
+    /// ```
+    fn extend_values<V: AnyType>(&mut self, values: impl IntoIterator<Item = V>);
+
+    /// Boxes and appends `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Array, ArrayExt};
+    /// let mut array = Array::new();
+    /// array.push_value("value");
+    /// assert_eq!(array.get_as::<&str>(0), Some(&"value"));
+    /// ```
+    fn push_value<V: AnyType>(&mut self, value: V);
+
+    /// Returns a reference to the element at `index` if it is of type `T`, or `None` if it isn't
+    /// or the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Array, ArrayExt};
+    /// let array = Array::from_values([1, 2]);
+    /// assert_eq!(array.get_as::<i32>(1), Some(&2));
+    /// assert_eq!(array.get_as::<i64>(1), None);
+    /// assert_eq!(array.get_as::<i32>(2), None);
+    /// ```
+    fn get_as<T: 'static>(&self, index: usize) -> Option<&T>;
+
+    /// Returns an iterator over the elements of type `T`, skipping the others.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Array, ArrayExt};
+    /// let mut array = Array::from_values([1, 2]);
+    /// array.push_value("three");
+    /// assert_eq!(array.iter_as::<i32>().sum::<i32>(), 3);
+    /// ```
+    fn iter_as<T: 'static>(&self) -> impl Iterator<Item = &T>;
+}
+
+impl ArrayExt for Array {
+    fn from_values<V: AnyType>(values: impl IntoIterator<Item = V>) -> Self {
+        let mut array = Array::new();
+        array.extend_values(values);
+        array
+    }
+
+    fn extend_values<V: AnyType>(&mut self, values: impl IntoIterator<Item = V>) {
+        self.extend(values.into_iter().map(|v| Box::new(v) as Box<dyn AnyType>));
+    }
+
+    fn push_value<V: AnyType>(&mut self, value: V) {
+        self.push(Box::new(value));
+    }
+
+    fn get_as<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.get(index)?.downcast_ref::<T>()
+    }
+
+    fn iter_as<T: 'static>(&self) -> impl Iterator<Item = &T> {
+        self.iter().filter_map(|v| v.downcast_ref::<T>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{object, Object};
+
+    use super::*;
+
+    #[test]
+    fn reads_back_elements_stored_in_an_object() {
+        let mut tags = Array::from_values(["a", "b"]);
+        tags.push_value(3);
+        tags.extend_values([object!({ nested: true, })]);
+        let obj = object!({
+            tags: tags,
+        });
+
+        let tags = obj.get_as::<Array>("tags").unwrap();
+        assert_eq!(tags.get_as::<&str>(1), Some(&"b"));
+        assert_eq!(tags.get_as::<i32>(2), Some(&3));
+        assert_eq!(
+            tags.iter_as::<&str>().copied().collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        let nested = tags.get_as::<Object>(3).unwrap();
+        assert_eq!(nested.get_as::<bool>("nested"), Some(&true));
+    }
+}
@@ -0,0 +1,228 @@
+//! An `Object` wrapper that can undo changes back to a checkpoint.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::{AnyType, Object};
+
+/// An `Object` that records the changes made since the last checkpoint, so they can be undone.
+///
+/// Each insert or remove logs the key and the value it displaced, which is moved into the log
+/// rather than cloned. [`Checkpointed::restore`] puts the displaced values back in reverse order,
+/// so snapshots cost nothing up front and memory in proportion to the changes since.
+///
+/// Reads go through `Deref` to the wrapped `Object`. Mutation is only possible through the
+/// methods of `Checkpointed`, so every change can be undone.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{object, Checkpointed};
+/// let mut object = Checkpointed::from(object!({
+///     volume: 5,
+/// }));
+/// object.insert("volume", 11);
+/// object.insert("muted", true);
+/// object.restore();
+/// assert_eq!(object.get_as::<i32>("volume"), Some(&5));
+/// assert!(object.get("muted").is_none());
+/// ```
+#[derive(Default)]
+pub struct Checkpointed {
+    object: Object,
+    log: Vec<(String, Option<Box<dyn AnyType>>)>,
+}
+
+impl Checkpointed {
+    /// Creates a new, empty `Checkpointed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Checkpointed;
+    /// let object = Checkpointed::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a key-value pair, recording the previous value of the key.
+    ///
+    /// As with [`Object::insert`], a new key is ignored if the wrapped `Object` is
+    /// [sealed](Object::seal), and nothing is recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Checkpointed;
+    /// let mut object = Checkpointed::new();
+    /// object.insert("key", "value");
+    /// assert_eq!(object.changes(), 1);
+    /// ```
+    pub fn insert<K: Into<String>, V: AnyType>(&mut self, key: K, value: V) {
+        let key = key.into();
+        if self.object.sealed && !self.object.map.contains_key(&key) {
+            return;
+        }
+        let previous = self.object.map.insert(key.clone(), Box::new(value));
+        self.log.push((key, previous));
+    }
+
+    /// Removes a key, returning whether it was present.
+    ///
+    /// The removed value is kept in the log so it can be restored, which is why it isn't returned.
+    /// Removing an absent key records nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Checkpointed};
+    /// let mut object = Checkpointed::from(object!({
+    ///     key: "value",
+    /// }));
+    /// assert!(object.remove("key"));
+    /// assert!(!object.remove("key"));
+    /// object.restore();
+    /// assert_eq!(object.get_as::<&str>("key"), Some(&"value"));
+    /// ```
+    pub fn remove(&mut self, key: &str) -> bool {
+        match self.object.map.remove(key) {
+            Some(value) => {
+                self.log.push((key.to_string(), Some(value)));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes the current state the one [`Checkpointed::restore`] returns to, dropping the
+    /// values recorded so far.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Checkpointed;
+    /// let mut object = Checkpointed::new();
+    /// object.insert("key", 1);
+    /// object.checkpoint();
+    /// object.insert("key", 2);
+    /// object.restore();
+    /// assert_eq!(object.get_as::<i32>("key"), Some(&1));
+    /// ```
+    pub fn checkpoint(&mut self) {
+        self.log.clear();
+    }
+
+    /// Undoes every change made since the last checkpoint, most recent first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Checkpointed;
+    /// let mut object = Checkpointed::new();
+    /// object.insert("key", 1);
+    /// object.restore();
+    /// assert!(object.is_empty());
+    /// ```
+    pub fn restore(&mut self) {
+        while let Some((key, previous)) = self.log.pop() {
+            match previous {
+                Some(value) => self.object.map.insert(key, value),
+                None => self.object.map.remove(&key),
+            };
+        }
+    }
+
+    /// Returns the number of changes recorded since the last checkpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Checkpointed;
+    /// let mut object = Checkpointed::new();
+    /// object.insert("a", 1);
+    /// object.insert("a", 2);
+    /// assert_eq!(object.changes(), 2);
+    /// ```
+    pub fn changes(&self) -> usize {
+        self.log.len()
+    }
+
+    /// Consumes the `Checkpointed`, returning the wrapped `Object` in its current state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Checkpointed;
+    /// let mut object = Checkpointed::new();
+    /// object.insert("key", "value");
+    /// let object = object.into_inner();
+    /// assert_eq!(object.get_as::<&str>("key"), Some(&"value"));
+    /// ```
+    pub fn into_inner(self) -> Object {
+        self.object
+    }
+}
+
+impl From<Object> for Checkpointed {
+    fn from(object: Object) -> Self {
+        Self {
+            object,
+            log: Vec::new(),
+        }
+    }
+}
+
+impl Deref for Checkpointed {
+    type Target = Object;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl Debug for Checkpointed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkpointed")
+            .field("object", &self.object)
+            .field("changes", &self.log.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn restores_the_last_checkpoint() {
+        let mut object = Checkpointed::from(object!({
+            a: 1,
+            b: "two",
+        }));
+        object.insert("a", 10);
+        object.insert("a", 100);
+        object.remove("b");
+        object.insert("b", 2.0);
+        object.insert("c", 3);
+        object.checkpoint();
+        object.remove("c");
+        object.insert("d", 4);
+        assert_eq!(object.changes(), 2);
+
+        object.restore();
+        assert_eq!(object.changes(), 0);
+        assert_eq!(
+            *object,
+            object!({
+                a: 100,
+                b: 2.0,
+                c: 3,
+            })
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Tables of conversions applied when reading values of a different type.
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+};
+
+use crate::Object;
+
+type Coercion = Box<dyn Fn(&dyn Any) -> Option<Box<dyn Any>> + Send + Sync>;
+
+/// A table of conversions between types, used by [`Object::get_coerced`].
+///
+/// Each entry converts a stored value of one type into a value of the requested type, keyed by
+/// the `TypeId`s of both. [`CoercionTable::numeric`] comes with the lossless conversions between
+/// the numeric primitives, e.g. from `i32` to `i64` or from `f32` to `f64`.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{object, CoercionTable};
+/// let mut table = CoercionTable::numeric();
+/// table.register::<&str, String>(|s| Some(s.to_string()));
+/// let obj = object!({
+///     count: 3,
+///     name: "app",
+/// });
+/// assert_eq!(obj.get_coerced::<i64>("count", &table), Some(3));
+/// assert_eq!(obj.get_coerced::<String>("name", &table), Some("app".to_string()));
+/// ```
+#[derive(Default)]
+pub struct CoercionTable {
+    coercions: HashMap<(TypeId, TypeId), Coercion>,
+}
+
+macro_rules! register_from {
+    ($table:expr; $($from:ty => $($to:ty),+;)*) => {
+        $($(
+            $table.register::<$from, $to>(|n| Some(<$to>::from(*n)));
+        )+)*
+    };
+}
+
+impl CoercionTable {
+    /// Creates an empty `CoercionTable`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::CoercionTable;
+    /// let table = CoercionTable::new();
+    /// assert!(table.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a `CoercionTable` holding every lossless conversion between the numeric
+    /// primitives, i.e. those for which the standard library implements `From`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::CoercionTable;
+    /// let table = CoercionTable::numeric();
+    /// assert!(table.contains::<i32, i64>());
+    /// assert!(table.contains::<u8, f32>());
+    /// assert!(!table.contains::<i64, i32>());
+    /// ```
+    pub fn numeric() -> Self {
+        let mut table = Self::new();
+        register_from! {
+            table;
+            i8 => i16, i32, i64, i128, isize, f32, f64;
+            i16 => i32, i64, i128, isize, f32, f64;
+            i32 => i64, i128, f64;
+            i64 => i128;
+            u8 => u16, u32, u64, u128, usize, i16, i32, i64, i128, isize, f32, f64;
+            u16 => u32, u64, u128, usize, i32, i64, i128, f32, f64;
+            u32 => u64, u128, i64, i128, f64;
+            u64 => u128, i128;
+            f32 => f64;
+        }
+        table
+    }
+
+    /// Registers a conversion from `F` to `T`, replacing any previous one for the same pair.
+    ///
+    /// `coerce` may return `None` to reject a particular value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, CoercionTable};
+    /// let mut table = CoercionTable::new();
+    /// table.register::<i64, i32>(|n| i32::try_from(*n).ok());
+    /// let obj = object!({
+    ///     small: 1i64,
+    ///     big: i64::MAX,
+    /// });
+    /// assert_eq!(obj.get_coerced::<i32>("small", &table), Some(1));
+    /// assert_eq!(obj.get_coerced::<i32>("big", &table), None);
+    /// ```
+    pub fn register<F: 'static, T: 'static>(&mut self, coerce: fn(&F) -> Option<T>) {
+        let coercion: Coercion = Box::new(move |value| {
+            let value = coerce(value.downcast_ref::<F>()?)?;
+            Some(Box::new(value))
+        });
+        self.coercions
+            .insert((TypeId::of::<F>(), TypeId::of::<T>()), coercion);
+    }
+
+    /// Returns `true` if the table has a conversion from `F` to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::CoercionTable;
+    /// let mut table = CoercionTable::new();
+    /// table.register::<bool, u8>(|b| Some(u8::from(*b)));
+    /// assert!(table.contains::<bool, u8>());
+    /// assert!(!table.contains::<u8, bool>());
+    /// ```
+    pub fn contains<F: 'static, T: 'static>(&self) -> bool {
+        self.coercions
+            .contains_key(&(TypeId::of::<F>(), TypeId::of::<T>()))
+    }
+
+    /// Returns the number of conversions in the table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::CoercionTable;
+    /// assert_eq!(CoercionTable::new().len(), 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.coercions.len()
+    }
+
+    /// Returns `true` if the table has no conversions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::CoercionTable;
+    /// assert!(CoercionTable::new().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.coercions.is_empty()
+    }
+
+    fn coerce<T: 'static>(&self, value: &dyn Any) -> Option<T> {
+        let coercion = self
+            .coercions
+            .get(&(Any::type_id(value), TypeId::of::<T>()))?;
+        coercion(value)?.downcast::<T>().ok().map(|value| *value)
+    }
+}
+
+impl Debug for CoercionTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoercionTable")
+            .field("coercions", &self.coercions.len())
+            .finish()
+    }
+}
+
+impl Object {
+    /// Returns the value corresponding to the key as a `T`, converting it with `table` if it is
+    /// of another type.
+    ///
+    /// A value that already is a `T` is cloned. Returns `None` if the key is absent, if `table`
+    /// has no conversion from the stored type to `T`, or if the conversion rejects the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, CoercionTable};
+    /// let table = CoercionTable::numeric();
+    /// let obj = object!({
+    ///     ratio: 0.5f32,
+    ///     count: 2i64,
+    /// });
+    /// assert_eq!(obj.get_coerced::<f64>("ratio", &table), Some(0.5));
+    /// assert_eq!(obj.get_coerced::<i64>("count", &table), Some(2));
+    /// assert_eq!(obj.get_coerced::<i32>("count", &table), None);
+    /// ```
+    pub fn get_coerced<T: Clone + 'static>(&self, key: &str, table: &CoercionTable) -> Option<T> {
+        let value = (**self.map.get(key)?).as_any();
+        match value.downcast_ref::<T>() {
+            Some(value) => Some(value.clone()),
+            None => table.coerce(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn widens_numbers_and_applies_custom_coercions() {
+        let mut table = CoercionTable::numeric();
+        table.register::<&str, i64>(|s| s.parse().ok());
+        let obj = object!({
+            byte: 7u8,
+            int: -4,
+            text: "12",
+            bad: "x",
+        });
+        assert_eq!(obj.get_coerced::<i64>("byte", &table), Some(7));
+        assert_eq!(obj.get_coerced::<f64>("byte", &table), Some(7.0));
+        assert_eq!(obj.get_coerced::<i128>("int", &table), Some(-4));
+        assert_eq!(obj.get_coerced::<u32>("int", &table), None);
+        assert_eq!(obj.get_coerced::<i64>("text", &table), Some(12));
+        assert_eq!(obj.get_coerced::<i64>("bad", &table), None);
+        assert_eq!(obj.get_coerced::<&str>("bad", &table), Some("x"));
+        assert_eq!(obj.get_coerced::<i64>("missing", &table), None);
+    }
+
+    #[test]
+    fn shares_a_table_across_threads() {
+        static TABLE: std::sync::LazyLock<CoercionTable> = std::sync::LazyLock::new(|| {
+            let mut table = CoercionTable::numeric();
+            table.register::<&str, i64>(|s| s.parse().ok());
+            table
+        });
+        std::thread::scope(|scope| {
+            for n in 0..4u8 {
+                scope.spawn(move || {
+                    let obj = object!({
+                        byte: n,
+                        text: "12",
+                    });
+                    assert_eq!(obj.get_coerced::<i64>("byte", &TABLE), Some(i64::from(n)));
+                    assert_eq!(obj.get_coerced::<i64>("text", &TABLE), Some(12));
+                });
+            }
+        });
+    }
+}
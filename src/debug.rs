@@ -0,0 +1,124 @@
+//! A depth-limited `Debug` adapter.
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::{AnyType, Object};
+
+impl Object {
+    /// Returns an adapter whose `Debug` output stops descending after `max_depth` levels of
+    /// nested objects, printing `{...}` in their place.
+    ///
+    /// The `Object` itself is at depth 0, so `debug_depth(0)` shows its entries but elides every
+    /// nested `Object`. Objects inside arrays count at the depth of the array. Both `{:?}` and
+    /// `{:#?}` are supported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     a: {
+    ///         b: {
+    ///             c: 1,
+    ///         },
+    ///     },
+    /// });
+    /// assert_eq!(
+    ///     format!("{:?}", object.debug_depth(1)),
+    ///     r#"Object { map: {"a": Object { map: {"b": {...}} }} }"#
+    /// );
+    /// ```
+    pub fn debug_depth(&self, max_depth: usize) -> impl Debug + '_ {
+        DepthLimited {
+            object: self,
+            remaining: max_depth,
+        }
+    }
+}
+
+struct DepthLimited<'a> {
+    object: &'a Object,
+    remaining: usize,
+}
+
+impl Debug for DepthLimited<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Object")
+            .field("map", &Entries(self))
+            .finish()
+    }
+}
+
+struct Entries<'a, 'b>(&'b DepthLimited<'a>);
+
+impl Debug for Entries<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Entries(limited) = self;
+        f.debug_map()
+            .entries(limited.object.map.iter().map(|(k, v)| {
+                let value = Value {
+                    value: &**v,
+                    remaining: limited.remaining,
+                };
+                (k, value)
+            }))
+            .finish()
+    }
+}
+
+struct Value<'a> {
+    value: &'a dyn AnyType,
+    remaining: usize,
+}
+
+impl Debug for Value<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(object) = self.value.downcast_ref::<Object>() {
+            return match self.remaining.checked_sub(1) {
+                Some(remaining) => DepthLimited { object, remaining }.fmt(f),
+                None => f.write_str("{...}"),
+            };
+        }
+        if let Some(array) = self.value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+            return f
+                .debug_list()
+                .entries(array.iter().map(|v| Value {
+                    value: &**v,
+                    remaining: self.remaining,
+                }))
+                .finish();
+        }
+        self.value.dyn_debug(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn elides_objects_past_the_limit() {
+        let object = object!({
+            list: vec![Box::new(object!({ x: 1, })) as Box<dyn AnyType>],
+            nested: {
+                inner: {
+                    value: 2,
+                },
+            },
+        });
+        assert_eq!(
+            format!("{:?}", object.debug_depth(0)),
+            r#"Object { map: {"list": [{...}], "nested": {...}} }"#
+        );
+        assert_eq!(
+            format!("{:?}", object.debug_depth(5)),
+            format!("{:?}", object)
+        );
+        assert_eq!(
+            format!("{:#?}", object.debug_depth(5)),
+            format!("{:#?}", object)
+        );
+    }
+}
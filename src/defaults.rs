@@ -0,0 +1,149 @@
+//! A read-only view that falls back to a defaults `Object`.
+
+use crate::{AnyType, Object};
+
+/// A view of an `Object` whose reads fall back to a second `Object` of defaults.
+///
+/// Created by [`Object::with_defaults`]. Nothing is copied: both objects are borrowed.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::object;
+/// let defaults = object!({
+///     host: "localhost",
+///     port: 80,
+/// });
+/// let config = object!({
+///     port: 8080,
+/// });
+/// let view = config.with_defaults(&defaults);
+/// assert_eq!(view.get_as::<i32>("port"), Some(&8080));
+/// assert_eq!(view.get_as::<&str>("host"), Some(&"localhost"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WithDefaults<'a> {
+    object: &'a Object,
+    defaults: &'a Object,
+}
+
+impl Object {
+    /// Returns a view where reads check the `Object` first, then `defaults`.
+    ///
+    /// See [`WithDefaults`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let defaults = object!({
+    ///     verbose: false,
+    /// });
+    /// let config = object!({});
+    /// assert_eq!(config.with_defaults(&defaults).get_as::<bool>("verbose"), Some(&false));
+    /// ```
+    pub fn with_defaults<'a>(&'a self, defaults: &'a Object) -> WithDefaults<'a> {
+        WithDefaults {
+            object: self,
+            defaults,
+        }
+    }
+}
+
+impl<'a> WithDefaults<'a> {
+    /// Returns the value corresponding to the key, from the object if present there or from the
+    /// defaults otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let defaults = object!({
+    ///     a: 1,
+    /// });
+    /// let object = object!({});
+    /// let view = object.with_defaults(&defaults);
+    /// assert!(view.get("a").is_some());
+    /// assert!(view.get("b").is_none());
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&'a dyn AnyType> {
+        self.object
+            .map
+            .get(key)
+            .or_else(|| self.defaults.map.get(key))
+            .map(|v| &**v)
+    }
+
+    /// Returns a reference to the value corresponding to the key if it is of type `T`, or `None`
+    /// if it isn't.
+    ///
+    /// The defaults are only consulted when the key is absent from the object. A value of the
+    /// wrong type in the object is not replaced by the default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let defaults = object!({
+    ///     a: 1,
+    /// });
+    /// let object = object!({
+    ///     a: "one",
+    /// });
+    /// let view = object.with_defaults(&defaults);
+    /// assert_eq!(view.get_as::<i32>("a"), None);
+    /// assert_eq!(view.get_as::<&str>("a"), Some(&"one"));
+    /// ```
+    pub fn get_as<T: 'static>(&self, key: &str) -> Option<&'a T> {
+        self.get(key)?.downcast_ref::<T>()
+    }
+
+    /// Returns `true` if the key is present in the object or in the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let defaults = object!({
+    ///     a: 1,
+    /// });
+    /// let object = object!({
+    ///     b: 2,
+    /// });
+    /// let view = object.with_defaults(&defaults);
+    /// assert!(view.contains_key("a"));
+    /// assert!(view.contains_key("b"));
+    /// assert!(!view.contains_key("c"));
+    /// ```
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.object.map.contains_key(key) || self.defaults.map.contains_key(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{object, Object};
+
+    #[test]
+    fn prefers_the_object_over_the_defaults() {
+        let defaults = object!({
+            level: "info",
+            retries: 3,
+            nested: {
+                a: 1,
+            },
+        });
+        let config = object!({
+            level: "debug",
+        });
+        let view = config.with_defaults(&defaults);
+        assert_eq!(view.get_as::<&str>("level"), Some(&"debug"));
+        assert_eq!(view.get_as::<i32>("retries"), Some(&3));
+        assert!(view.get_as::<Object>("nested").is_some());
+        assert!(view.get("missing").is_none());
+
+        let config = object!({});
+        let retries = config.with_defaults(&defaults).get_as::<i32>("retries");
+        assert_eq!(retries, Some(&3));
+    }
+}
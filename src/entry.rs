@@ -0,0 +1,374 @@
+//! A typed view of a single entry of an `Object`, mirroring `std`'s entry API.
+
+use std::{any, collections::btree_map, marker::PhantomData};
+
+use crate::{AnyType, Object, TypeConflict};
+
+/// A view into a single entry of an `Object`, expected to hold a value of type `T`.
+///
+/// Created by [`Object::typed_entry`].
+#[derive(Debug)]
+pub enum TypedEntry<'a, T> {
+    /// The key is present. Its value may or may not be of type `T`.
+    Occupied(OccupiedEntry<'a, T>),
+    /// The key is absent.
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// A view into an occupied entry of an `Object`, part of [`TypedEntry`].
+///
+/// The stored value may be of another type than `T`, in which case the typed accessors return a
+/// [`TypeConflict`] and [`OccupiedEntry::get_boxed`] still gives access to it.
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, T> {
+    entry: btree_map::OccupiedEntry<'a, String, Box<dyn AnyType>>,
+    marker: PhantomData<fn() -> T>,
+}
+
+/// A view into a vacant entry of an `Object`, part of [`TypedEntry`].
+#[derive(Debug)]
+pub struct VacantEntry<'a, T> {
+    entry: btree_map::VacantEntry<'a, String, Box<dyn AnyType>>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: AnyType> TypedEntry<'a, T> {
+    /// Returns the key of the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// assert_eq!(object.typed_entry::<i32>("key").key(), "key");
+    /// ```
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns a mutable reference to the value, inserting `default` if the key is absent.
+    ///
+    /// Returns a [`TypeConflict`] if the key holds a value of another type, which is left
+    /// untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut object = object!({ name: "app", });
+    /// *object.typed_entry("count").or_insert(0).unwrap() += 1;
+    /// assert_eq!(object.get_as::<i32>("count"), Some(&1));
+    /// assert!(object.typed_entry("name").or_insert(0).is_err());
+    /// ```
+    pub fn or_insert(self, default: T) -> Result<&'a mut T, TypeConflict> {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns a mutable reference to the value, inserting the result of `default` if the key is
+    /// absent.
+    ///
+    /// Returns a [`TypeConflict`] if the key holds a value of another type, which is left
+    /// untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.typed_entry("list").or_insert_with(Vec::new).unwrap().push(1);
+    /// assert_eq!(object.get_as::<Vec<i32>>("list"), Some(&vec![1]));
+    /// ```
+    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> Result<&'a mut T, TypeConflict> {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => Ok(entry.insert(default())),
+        }
+    }
+
+    /// Calls `f` with the value if the key holds a value of type `T`, then returns the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut object = object!({ count: 1, });
+    /// object.typed_entry::<i32>("count").and_modify(|n| *n += 1).or_insert(0).unwrap();
+    /// object.typed_entry::<i32>("other").and_modify(|n| *n += 1).or_insert(0).unwrap();
+    /// assert_eq!(object, object!({ count: 2, other: 0, }));
+    /// ```
+    pub fn and_modify(mut self, f: impl FnOnce(&mut T)) -> Self {
+        if let Self::Occupied(entry) = &mut self {
+            if let Ok(value) = entry.get_mut() {
+                f(value);
+            }
+        }
+        self
+    }
+}
+
+impl<'a, T: AnyType> OccupiedEntry<'a, T> {
+    /// Returns the key of the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: 1, });
+    /// if let TypedEntry::Occupied(entry) = object.typed_entry::<i32>("a") {
+    ///     assert_eq!(entry.key(), "a");
+    /// }
+    /// ```
+    pub fn key(&self) -> &str {
+        self.entry.key()
+    }
+
+    /// Returns `true` if the stored value is of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: 1, });
+    /// if let TypedEntry::Occupied(entry) = object.typed_entry::<&str>("a") {
+    ///     assert!(!entry.is_type());
+    /// }
+    /// ```
+    pub fn is_type(&self) -> bool {
+        (**self.entry.get()).as_any().is::<T>()
+    }
+
+    /// Returns a reference to the value, or a [`TypeConflict`] if it is not of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: 1, });
+    /// if let TypedEntry::Occupied(entry) = object.typed_entry::<i32>("a") {
+    ///     assert_eq!(entry.get(), Ok(&1));
+    /// }
+    /// ```
+    pub fn get(&self) -> Result<&T, TypeConflict> {
+        let value = &**self.entry.get();
+        value
+            .downcast_ref::<T>()
+            .ok_or_else(|| self.conflict(value))
+    }
+
+    /// Returns a mutable reference to the value, or a [`TypeConflict`] if it is not of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: 1, });
+    /// if let TypedEntry::Occupied(mut entry) = object.typed_entry::<i32>("a") {
+    ///     *entry.get_mut().unwrap() = 2;
+    /// }
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&2));
+    /// ```
+    pub fn get_mut(&mut self) -> Result<&mut T, TypeConflict> {
+        if !self.is_type() {
+            return Err(self.conflict(&**self.entry.get()));
+        }
+        Ok((**self.entry.get_mut())
+            .downcast_mut::<T>()
+            .expect("the value is of type `T`"))
+    }
+
+    /// Converts the entry into a mutable reference to the value with the lifetime of the
+    /// `Object`, or returns a [`TypeConflict`] if it is not of type `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: 1, });
+    /// if let TypedEntry::Occupied(entry) = object.typed_entry::<i32>("a") {
+    ///     *entry.into_mut().unwrap() += 1;
+    /// }
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&2));
+    /// ```
+    pub fn into_mut(self) -> Result<&'a mut T, TypeConflict> {
+        if !self.is_type() {
+            return Err(self.conflict(&**self.entry.get()));
+        }
+        Ok((**self.entry.into_mut())
+            .downcast_mut::<T>()
+            .expect("the value is of type `T`"))
+    }
+
+    /// Returns the stored value without knowing its type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, AnyType, TypedEntry};
+    /// let mut object = object!({ a: "text", });
+    /// if let TypedEntry::Occupied(entry) = object.typed_entry::<i32>("a") {
+    ///     assert_eq!(entry.get_boxed().dyn_type_name(), "&str");
+    /// }
+    /// ```
+    pub fn get_boxed(&self) -> &dyn AnyType {
+        &**self.entry.get()
+    }
+
+    /// Replaces the value with `value`, whatever its previous type, and returns the old one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: "text", });
+    /// if let TypedEntry::Occupied(mut entry) = object.typed_entry("a") {
+    ///     let old = entry.insert(1);
+    ///     assert_eq!(old.downcast_ref::<&str>(), Some(&"text"));
+    /// }
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&1));
+    /// ```
+    pub fn insert(&mut self, value: T) -> Box<dyn AnyType> {
+        self.entry.insert(Box::new(value))
+    }
+
+    /// Removes the entry from the `Object` and returns its value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ a: 1, });
+    /// if let TypedEntry::Occupied(entry) = object.typed_entry::<i32>("a") {
+    ///     assert_eq!(entry.remove().downcast_ref::<i32>(), Some(&1));
+    /// }
+    /// assert!(object.is_empty());
+    /// ```
+    pub fn remove(self) -> Box<dyn AnyType> {
+        self.entry.remove()
+    }
+
+    fn conflict(&self, value: &dyn AnyType) -> TypeConflict {
+        TypeConflict {
+            key: self.entry.key().clone(),
+            expected: any::type_name::<T>(),
+            found: value.dyn_type_name(),
+        }
+    }
+}
+
+impl<'a, T: AnyType> VacantEntry<'a, T> {
+    /// Returns the key of the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Object, TypedEntry};
+    /// let mut object = Object::new();
+    /// if let TypedEntry::Vacant(entry) = object.typed_entry::<i32>("a") {
+    ///     assert_eq!(entry.key(), "a");
+    /// }
+    /// ```
+    pub fn key(&self) -> &str {
+        self.entry.key()
+    }
+
+    /// Inserts `value` under the key of the entry and returns a mutable reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Object, TypedEntry};
+    /// let mut object = Object::new();
+    /// if let TypedEntry::Vacant(entry) = object.typed_entry("a") {
+    ///     *entry.insert(1) += 1;
+    /// }
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&2));
+    /// ```
+    pub fn insert(self, value: T) -> &'a mut T {
+        (**self.entry.insert(Box::new(value)))
+            .downcast_mut::<T>()
+            .expect("the value was just inserted")
+    }
+}
+
+impl Object {
+    /// Returns the entry for the key, expecting a value of type `T`.
+    ///
+    /// This mirrors `BTreeMap::entry`, but [`TypedEntry::Occupied`] gives typed access to the
+    /// value and reports a [`TypeConflict`] when the key holds a value of another type, so
+    /// inspecting and conditionally changing a value takes a single lookup. As with
+    /// `BTreeMap::entry`, a [`VacantEntry`] always inserts, even if the `Object` is
+    /// [sealed](Object::seal); check [`Object::is_sealed`] first where that matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, TypedEntry};
+    /// let mut object = object!({ hits: 1, });
+    /// match object.typed_entry::<i32>("hits") {
+    ///     TypedEntry::Occupied(mut entry) => {
+    ///         let hits = entry.get_mut().unwrap();
+    ///         if *hits < 10 {
+    ///             *hits += 1;
+    ///         }
+    ///     }
+    ///     TypedEntry::Vacant(entry) => {
+    ///         entry.insert(1);
+    ///     }
+    /// }
+    /// assert_eq!(object.get_as::<i32>("hits"), Some(&2));
+    /// ```
+    pub fn typed_entry<T: AnyType>(&mut self, key: impl Into<String>) -> TypedEntry<'_, T> {
+        match self.map.entry(key.into()) {
+            btree_map::Entry::Occupied(entry) => TypedEntry::Occupied(OccupiedEntry {
+                entry,
+                marker: PhantomData,
+            }),
+            btree_map::Entry::Vacant(entry) => TypedEntry::Vacant(VacantEntry {
+                entry,
+                marker: PhantomData,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn typed_entry_reports_wrong_type() {
+        let mut object = object!({ a: "text", });
+        let TypedEntry::Occupied(mut entry) = object.typed_entry::<i32>("a") else {
+            panic!("`a` is present");
+        };
+        let conflict = TypeConflict {
+            key: "a".to_string(),
+            expected: "i32",
+            found: "&str",
+        };
+        assert!(!entry.is_type());
+        assert_eq!(entry.get(), Err(conflict.clone()));
+        assert_eq!(entry.get_mut(), Err(conflict.clone()));
+        entry.insert(5);
+        assert_eq!(entry.get(), Ok(&5));
+        assert_eq!(entry.into_mut(), Ok(&mut 5));
+
+        assert!(matches!(
+            object.typed_entry::<i32>("b"),
+            TypedEntry::Vacant(_)
+        ));
+        assert_eq!(
+            object.typed_entry::<bool>("a").or_insert(true),
+            Err(TypeConflict {
+                expected: "bool",
+                found: "i32",
+                ..conflict
+            })
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Exporting an `Object` as environment variables.
+
+use crate::{AnyType, Null, Object};
+
+impl Object {
+    /// Flattens the `Object` into environment variable pairs, e.g. for spawning a subprocess.
+    ///
+    /// Nested keys are joined with `__` and uppercased, then `prefix` is prepended as-is, so
+    /// `db.host` with the prefix `"APP_"` becomes `APP_DB__HOST`. Pairs are returned in key
+    /// order, and empty nested objects produce none.
+    ///
+    /// Leaves are rendered as follows:
+    ///
+    /// - `String`, `&str` and `char` are written as-is, without quotes.
+    /// - `bool` and the integer and float primitives use `Display`.
+    /// - [`Null`] and `()` become an empty string.
+    /// - Any other value, including arrays stored as `Vec<Box<dyn AnyType>>`, falls back to its
+    ///   `Debug` representation, since whether a type implements `Display` can't be known at
+    ///   runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Null};
+    /// let config = object!({
+    ///     db: {
+    ///         host: "localhost",
+    ///         port: 5432,
+    ///     },
+    ///     debug: true,
+    ///     token: Null,
+    /// });
+    /// assert_eq!(
+    ///     config.to_env_vars("APP_"),
+    ///     [
+    ///         ("APP_DB__HOST".to_string(), "localhost".to_string()),
+    ///         ("APP_DB__PORT".to_string(), "5432".to_string()),
+    ///         ("APP_DEBUG".to_string(), "true".to_string()),
+    ///         ("APP_TOKEN".to_string(), String::new()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_env_vars(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut vars = Vec::new();
+        collect_env_vars(self, prefix, &mut vars);
+        vars
+    }
+}
+
+fn collect_env_vars(object: &Object, prefix: &str, vars: &mut Vec<(String, String)>) {
+    for (key, value) in &object.map {
+        let name = format!("{prefix}{}", key.to_uppercase());
+        match value.as_object() {
+            Some(nested) => collect_env_vars(nested, &format!("{name}__"), vars),
+            None => vars.push((name, render(&**value))),
+        }
+    }
+}
+
+macro_rules! render_display {
+    ($value:ident, $($ty:ty),*) => {
+        $(
+            if let Some(value) = $value.downcast_ref::<$ty>() {
+                return value.to_string();
+            }
+        )*
+    };
+}
+
+fn render(value: &dyn AnyType) -> String {
+    if value.as_any().is::<Null>() || value.as_any().is::<()>() {
+        return String::new();
+    }
+    render_display!(
+        value, String, &str, char, bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128,
+        usize, f32, f64
+    );
+    format!("{value:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn renders_leaves_and_falls_back_to_debug() {
+        let object = object!({
+            list: vec![Box::new(1) as Box<dyn AnyType>, Box::new("a")],
+            name: "app".to_string(),
+            letter: 'x',
+            ratio: 0.5f32,
+            unit: (),
+            opt: Some(1),
+            a: {
+                b: {
+                    c: -1i64,
+                },
+                empty: {},
+            },
+        });
+        assert_eq!(
+            object.to_env_vars(""),
+            [
+                ("A__B__C", "-1"),
+                ("LETTER", "x"),
+                ("LIST", r#"[1, "a"]"#),
+                ("NAME", "app"),
+                ("OPT", "Some(1)"),
+                ("RATIO", "0.5"),
+                ("UNIT", ""),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+    }
+}
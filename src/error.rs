@@ -0,0 +1,235 @@
+//! Error types.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+/// An error returned when a key holds a value of a different type than the one requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeConflict {
+    /// The conflicting key.
+    pub key: String,
+    /// The name of the requested type.
+    pub expected: &'static str,
+    /// The name of the type of the existing value.
+    pub found: &'static str,
+}
+
+impl Display for TypeConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key `{}` holds a value of type `{}`, expected `{}`",
+            self.key, self.found, self.expected
+        )
+    }
+}
+
+impl Error for TypeConflict {}
+
+/// An error returned when a value can't be inserted under a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertError {
+    /// The key holds a value of another type.
+    TypeConflict(TypeConflict),
+    /// The key is absent and the `Object` is [sealed](crate::Object::seal).
+    Sealed {
+        /// The key that couldn't be added.
+        key: String,
+    },
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeConflict(conflict) => Display::fmt(conflict, f),
+            Self::Sealed { key } => write!(f, "key `{key}` can't be added to a sealed object"),
+        }
+    }
+}
+
+impl Error for InsertError {}
+
+impl From<TypeConflict> for InsertError {
+    fn from(conflict: TypeConflict) -> Self {
+        Self::TypeConflict(conflict)
+    }
+}
+
+/// An error returned when several keys would end up with the same name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCollision {
+    /// The key that more than one entry would be stored under.
+    pub key: String,
+}
+
+impl Display for KeyCollision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "more than one entry would be stored under key `{}`",
+            self.key
+        )
+    }
+}
+
+impl Error for KeyCollision {}
+
+/// An error returned when a value can't be read, tagged with a caller-supplied context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessError {
+    /// The key is absent.
+    Missing {
+        /// The missing key.
+        key: String,
+        /// The context supplied by the caller.
+        context: String,
+    },
+    /// The key holds a value of another type.
+    WrongType {
+        /// The offending key.
+        key: String,
+        /// The name of the requested type.
+        expected: &'static str,
+        /// The name of the type of the existing value.
+        found: &'static str,
+        /// The context supplied by the caller.
+        context: String,
+    },
+}
+
+impl Display for AccessError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { key, context } => write!(f, "{context}: key `{key}` is missing"),
+            Self::WrongType {
+                key,
+                expected,
+                found,
+                context,
+            } => write!(
+                f,
+                "{context}: key `{key}` holds a value of type `{found}`, expected `{expected}`"
+            ),
+        }
+    }
+}
+
+impl Error for AccessError {}
+
+/// An error returned when a dotted path can't be followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// The value at `path` is not an `Object`, so it can't be descended into.
+    NotAnObject {
+        /// The dotted path of the offending value.
+        path: String,
+    },
+    /// The value at `path` is not of the requested type.
+    WrongType {
+        /// The dotted path of the offending value.
+        path: String,
+        /// The name of the requested type.
+        expected: &'static str,
+        /// The name of the type of the existing value.
+        found: &'static str,
+    },
+    /// The key at `path` is absent and the `Object` holding it is [sealed](crate::Object::seal).
+    Sealed {
+        /// The dotted path of the key that couldn't be added.
+        path: String,
+    },
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnObject { path } => write!(f, "value at `{path}` is not an object"),
+            Self::WrongType {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "value at `{path}` is of type `{found}`, expected `{expected}`"
+            ),
+            Self::Sealed { path } => write!(f, "key `{path}` can't be added to a sealed object"),
+        }
+    }
+}
+
+impl Error for PathError {}
+
+/// An error returned when an `Object` can't be converted to another format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The value at `path` has a type with no representation in the target format.
+    Unsupported {
+        /// The dotted path of the offending value.
+        path: String,
+    },
+    /// The number at `path` doesn't fit in the numeric types of the target format.
+    OutOfRange {
+        /// The dotted path of the offending value.
+        path: String,
+    },
+    /// A mapping key at `path` can't be turned into a string.
+    UnsupportedKey {
+        /// The dotted path of the mapping holding the key.
+        path: String,
+    },
+    /// Two keys of a mapping turn into the same string, such as `1` and `"1"`.
+    DuplicateKey {
+        /// The dotted path of the value stored under the key.
+        path: String,
+    },
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported { path } => {
+                write!(
+                    f,
+                    "value at `{path}` has no representation in the target format"
+                )
+            }
+            Self::OutOfRange { path } => write!(f, "number at `{path}` is out of range"),
+            Self::UnsupportedKey { path } => {
+                write!(f, "mapping at `{path}` has a key that is not a string")
+            }
+            Self::DuplicateKey { path } => {
+                write!(f, "more than one mapping key turns into `{path}`")
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+/// An error returned when an `Object` can't be wrapped in a [`TypedObject`](crate::TypedObject).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// A key of the schema holds a value of another type.
+    TypeConflict(TypeConflict),
+    /// The `Object` is [sealed](crate::Object::seal), so absent keys of the schema couldn't be set.
+    Sealed,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeConflict(conflict) => Display::fmt(conflict, f),
+            Self::Sealed => f.write_str("object is sealed"),
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+impl From<TypeConflict> for SchemaError {
+    fn from(conflict: TypeConflict) -> Self {
+        Self::TypeConflict(conflict)
+    }
+}
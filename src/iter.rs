@@ -0,0 +1,52 @@
+//! Iterator adapters for the entries of an `Object`.
+
+use crate::AnyType;
+
+/// Adapters for iterators over the entries of an `Object`, such as the one returned by `iter`.
+///
+/// This trait is implemented for every iterator over `(&String, &Box<dyn AnyType>)`.
+pub trait ObjectIterExt<'a>: Iterator<Item = (&'a String, &'a Box<dyn AnyType>)> + Sized {
+    /// Keeps the entries whose value is of type `T`, downcasting the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, ObjectIterExt};
+    /// let obj = object!({
+    ///     a: 1,
+    ///     b: "two",
+    ///     c: 3,
+    /// });
+    /// let big: Vec<_> = obj.iter().typed::<i32>().filter(|(_, n)| **n > 1).collect();
+    /// assert_eq!(big, [(&"c".to_string(), &3)]);
+    /// ```
+    fn typed<T: 'static>(self) -> impl Iterator<Item = (&'a String, &'a T)> {
+        self.filter_map(|(key, value)| Some((key, (**value).downcast_ref::<T>()?)))
+    }
+}
+
+impl<'a, I> ObjectIterExt<'a> for I where I: Iterator<Item = (&'a String, &'a Box<dyn AnyType>)> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn filters_and_downcasts_entries() {
+        let obj = object!({
+            a: 1,
+            b: 2.0,
+            c: 3,
+            d: {},
+        });
+        let keys: Vec<_> = obj.iter().typed::<i32>().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["a", "c"]);
+        assert_eq!(obj.iter().typed::<f64>().count(), 1);
+        assert_eq!(
+            obj.iter().rev().typed::<i32>().next().map(|(_, n)| *n),
+            Some(3)
+        );
+    }
+}
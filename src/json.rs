@@ -0,0 +1,761 @@
+//! JSON serialization for `Object`.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter, Write},
+};
+
+use crate::{AnyType, Null, Object};
+
+/// An error returned when converting between an `Object` and JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonError {
+    /// The value at `path` has a type with no JSON representation.
+    Unsupported {
+        /// The dotted path of the offending value.
+        path: String,
+    },
+    /// The float at `path` is NaN or infinite.
+    NonFinite {
+        /// The dotted path of the offending value.
+        path: String,
+    },
+    /// A JSON value that must be an object is not.
+    NotAnObject,
+    /// The object or array at `path` is nested deeper than the configured maximum.
+    TooDeep {
+        /// The dotted path of the offending value.
+        path: String,
+    },
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported { path } => {
+                write!(f, "value at `{path}` cannot be represented as JSON")
+            }
+            Self::NonFinite { path } => write!(f, "float at `{path}` is not finite"),
+            Self::NotAnObject => write!(f, "JSON value is not an object"),
+            Self::TooDeep { path } => {
+                write!(f, "value at `{path}` exceeds the maximum nesting depth")
+            }
+        }
+    }
+}
+
+impl Error for JsonError {}
+
+/// Options for [`Object::serialize_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct SerializeOptions {
+    /// Whether the output is indented with two spaces. Defaults to `false`.
+    pub pretty: bool,
+    /// A function applied to every key, including those of nested objects, as it is written.
+    /// Defaults to `None`, which writes keys unchanged.
+    pub key_transform: Option<fn(&str) -> String>,
+    /// The maximum nesting depth, counted like [`Object::max_depth`]. Deeper objects fail with
+    /// [`JsonError::TooDeep`]. Defaults to [`Object::DEFAULT_MAX_DEPTH`].
+    pub max_depth: usize,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            pretty: false,
+            key_transform: None,
+            max_depth: Object::DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+impl Object {
+    /// Serializes the `Object` to a JSON string.
+    ///
+    /// If `pretty` is `true` the output is indented with two spaces, otherwise it is compact.
+    /// Numbers are written using their concrete type, so integers never become floats.
+    ///
+    /// Supported values are `bool`, the integer and float primitives, `char`, `String`, `&str`,
+    /// `Null` and `()` (both written as `null`), arrays stored as `Vec<Box<dyn AnyType>>` and
+    /// nested `Object`s. Any other value results in an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({
+    ///     name: "dynamic",
+    ///     count: 3i64,
+    ///     ratio: 0.5,
+    /// });
+    /// assert_eq!(
+    ///     obj.to_json_string(false).unwrap(),
+    ///     r#"{"count":3,"name":"dynamic","ratio":0.5}"#
+    /// );
+    /// ```
+    pub fn to_json_string(&self, pretty: bool) -> Result<String, JsonError> {
+        self.serialize_with(&SerializeOptions {
+            pretty,
+            ..SerializeOptions::default()
+        })
+    }
+
+    /// Serializes the `Object` to a JSON string, as configured by `options`.
+    ///
+    /// This works like [`Object::to_json_string`], but can also rename keys on the way out, which
+    /// keeps the keys in memory canonical while adapting the output. Entries are still written in
+    /// the order of the original keys, and keys that are transformed into the same string are
+    /// written twice. Error paths use the original keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, SerializeOptions};
+    /// fn camel_case(key: &str) -> String {
+    ///     let mut parts = key.split('_');
+    ///     let first = parts.next().unwrap_or_default().to_string();
+    ///     parts.fold(first, |mut out, part| {
+    ///         let mut chars = part.chars();
+    ///         out.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+    ///         out.extend(chars);
+    ///         out
+    ///     })
+    /// }
+    /// let obj = object!({
+    ///     max_retries: 3,
+    /// });
+    /// let options = SerializeOptions {
+    ///     key_transform: Some(camel_case),
+    ///     ..SerializeOptions::default()
+    /// };
+    /// assert_eq!(obj.serialize_with(&options).unwrap(), r#"{"maxRetries":3}"#);
+    /// ```
+    pub fn serialize_with(&self, options: &SerializeOptions) -> Result<String, JsonError> {
+        let mut out = String::new();
+        match JsonWriter::new(&mut out, options).write_object(self) {
+            Ok(()) => Ok(out),
+            Err(WriteError::Json(error)) => Err(error),
+            Err(WriteError::Fmt(_)) => unreachable!("writing to a String never fails"),
+        }
+    }
+
+    /// Writes the `Object` as compact JSON to any [`fmt::Write`] sink, without building an
+    /// intermediate `String`.
+    ///
+    /// The output is the same as [`Object::to_json_string`] with `pretty` set to `false`, and
+    /// numbers are likewise written using their concrete type. Nothing is allocated apart from
+    /// the bookkeeping of the current path, so a sink backed by a fixed-size buffer works too.
+    ///
+    /// # Errors
+    ///
+    /// Returns `fmt::Error` if the sink fails or if a value can't be represented as JSON, in which
+    /// case the output written so far is incomplete. Use [`Object::to_json_string`] to find out
+    /// which value is at fault.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::{self, Write};
+    /// use dynamic_object::object;
+    ///
+    /// struct Buffer {
+    ///     bytes: [u8; 32],
+    ///     len: usize,
+    /// }
+    ///
+    /// impl Write for Buffer {
+    ///     fn write_str(&mut self, s: &str) -> fmt::Result {
+    ///         let end = self.len + s.len();
+    ///         self.bytes.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+    ///         self.len = end;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let obj = object!({
+    ///     id: 7u8,
+    ///     ok: true,
+    /// });
+    /// let mut buffer = Buffer { bytes: [0; 32], len: 0 };
+    /// obj.write_json(&mut buffer).unwrap();
+    /// assert_eq!(&buffer.bytes[..buffer.len], br#"{"id":7,"ok":true}"#);
+    ///
+    /// let mut tiny = Buffer { bytes: [0; 32], len: 30 };
+    /// assert!(obj.write_json(&mut tiny).is_err());
+    /// ```
+    pub fn write_json<W: Write>(&self, w: &mut W) -> fmt::Result {
+        JsonWriter::new(w, &SerializeOptions::default())
+            .write_object(self)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl Object {
+    /// Applies a JSON Merge Patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)) to the `Object`.
+    ///
+    /// `null` values in the patch delete the corresponding key, nested patch objects are merged
+    /// recursively, and any other value replaces the existing one. JSON values are stored as
+    /// `bool`, `i64`, `u64` or `f64`, `String`, [`Null`] for `null`, `Vec<Box<dyn AnyType>>` for
+    /// arrays and `Object` for objects.
+    ///
+    /// Members that would add a key to a [sealed](Object::seal) `Object` are skipped.
+    ///
+    /// Returns `JsonError::NotAnObject` if the patch itself is not a JSON object.
+    ///
+    /// This method requires the `serde_json` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut config = object!({
+    ///     host: "localhost",
+    ///     db: {
+    ///         user: "admin",
+    ///         password: "secret",
+    ///     },
+    /// });
+    /// let patch = serde_json::json!({
+    ///     "host": "example.com",
+    ///     "db": { "password": null },
+    /// });
+    /// config.apply_json_patch(&patch).unwrap();
+    /// assert_eq!(config.get_as::<String>("host"), Some(&"example.com".to_string()));
+    /// let db = config.get_as::<dynamic_object::Object>("db").unwrap();
+    /// assert!(db.get("password").is_none());
+    /// ```
+    pub fn apply_json_patch(&mut self, patch: &serde_json::Value) -> Result<(), JsonError> {
+        match patch {
+            serde_json::Value::Object(patch) => {
+                self.merge_json_map(patch);
+                Ok(())
+            }
+            _ => Err(JsonError::NotAnObject),
+        }
+    }
+
+    fn merge_json_map(&mut self, patch: &serde_json::Map<String, serde_json::Value>) {
+        for (key, value) in patch {
+            if self.sealed && !value.is_null() && !self.map.contains_key(key) {
+                continue;
+            }
+            match value {
+                serde_json::Value::Null => {
+                    self.map.remove(key);
+                }
+                serde_json::Value::Object(inner) => {
+                    let existing = self
+                        .map
+                        .get_mut(key)
+                        .and_then(|v| (**v).downcast_mut::<Object>());
+                    match existing {
+                        Some(target) => target.merge_json_map(inner),
+                        None => {
+                            let mut target = Object::new();
+                            target.merge_json_map(inner);
+                            self.map.insert(key.clone(), Box::new(target));
+                        }
+                    }
+                }
+                value => {
+                    self.map.insert(key.clone(), from_json_value(value));
+                }
+            }
+        }
+    }
+}
+
+/// Converts a JSON value as-is, keeping `null` members of objects as [`Null`], as a merge patch
+/// does for any value that isn't itself a patch object.
+#[cfg(feature = "serde_json")]
+fn from_json_value(value: &serde_json::Value) -> Box<dyn AnyType> {
+    use serde_json::Value;
+
+    match value {
+        Value::Null => Box::new(Null),
+        Value::Bool(b) => Box::new(*b),
+        Value::Number(n) => {
+            if let Some(n) = n.as_i64() {
+                Box::new(n)
+            } else if let Some(n) = n.as_u64() {
+                Box::new(n)
+            } else {
+                Box::new(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        Value::String(s) => Box::new(s.clone()),
+        Value::Array(array) => Box::new(array.iter().map(from_json_value).collect::<Vec<_>>()),
+        Value::Object(map) => Box::new(Object::from_map(
+            map.iter()
+                .map(|(key, value)| (key.clone(), from_json_value(value)))
+                .collect(),
+        )),
+    }
+}
+
+/// An error raised while writing JSON: either the value can't be represented, or the sink
+/// failed.
+enum WriteError {
+    Json(JsonError),
+    Fmt(fmt::Error),
+}
+
+impl From<JsonError> for WriteError {
+    fn from(error: JsonError) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<fmt::Error> for WriteError {
+    fn from(error: fmt::Error) -> Self {
+        Self::Fmt(error)
+    }
+}
+
+enum Segment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl Display for Segment<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key) => f.write_str(key),
+            Self::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+struct JsonWriter<'a, W> {
+    out: &'a mut W,
+    pretty: bool,
+    key_transform: Option<fn(&str) -> String>,
+    max_depth: usize,
+    path: Vec<Segment<'a>>,
+}
+
+macro_rules! write_integers {
+    ($writer:ident, $value:ident, $($t:ty),*) => {
+        $(
+            if let Some(n) = $value.downcast_ref::<$t>() {
+                write!($writer.out, "{n}")?;
+                return Ok(());
+            }
+        )*
+    };
+}
+
+macro_rules! write_floats {
+    ($writer:ident, $value:ident, $($t:ty),*) => {
+        $(
+            if let Some(n) = $value.downcast_ref::<$t>() {
+                if !n.is_finite() {
+                    return Err(JsonError::NonFinite { path: $writer.path() }.into());
+                }
+                write!($writer.out, "{n}")?;
+                if n.fract() == 0.0 {
+                    $writer.out.write_str(".0")?;
+                }
+                return Ok(());
+            }
+        )*
+    };
+}
+
+impl<'a, W: Write> JsonWriter<'a, W> {
+    fn new(out: &'a mut W, options: &SerializeOptions) -> Self {
+        Self {
+            out,
+            pretty: options.pretty,
+            key_transform: options.key_transform,
+            max_depth: options.max_depth,
+            path: Vec::new(),
+        }
+    }
+
+    /// Checks the depth of the object or array about to be written, which is one more than the
+    /// number of containers holding it.
+    fn enter(&self) -> Result<(), WriteError> {
+        if self.path.len() >= self.max_depth {
+            return Err(JsonError::TooDeep { path: self.path() }.into());
+        }
+        Ok(())
+    }
+
+    fn path(&self) -> String {
+        let mut path = String::new();
+        for (i, segment) in self.path.iter().enumerate() {
+            if i > 0 {
+                path.push('.');
+            }
+            let _ = write!(path, "{segment}");
+        }
+        path
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        if self.pretty {
+            self.out.write_char('\n')?;
+            for _ in 0..self.path.len() {
+                self.out.write_str("  ")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_object(&mut self, object: &'a Object) -> Result<(), WriteError> {
+        self.enter()?;
+        if object.map.is_empty() {
+            self.out.write_str("{}")?;
+            return Ok(());
+        }
+        self.out.write_char('{')?;
+        for (i, (key, value)) in object.map.iter().enumerate() {
+            if i > 0 {
+                self.out.write_char(',')?;
+            }
+            self.path.push(Segment::Key(key));
+            self.newline()?;
+            match self.key_transform {
+                Some(transform) => self.write_str(&transform(key))?,
+                None => self.write_str(key)?,
+            }
+            self.out.write_char(':')?;
+            if self.pretty {
+                self.out.write_char(' ')?;
+            }
+            self.write_value(&**value)?;
+            self.path.pop();
+        }
+        self.newline()?;
+        self.out.write_char('}')?;
+        Ok(())
+    }
+
+    fn write_value(&mut self, value: &'a dyn AnyType) -> Result<(), WriteError> {
+        if let Some(object) = value.downcast_ref::<Object>() {
+            return self.write_object(object);
+        }
+        if let Some(array) = value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+            return self.write_array(array);
+        }
+        if let Some(b) = value.downcast_ref::<bool>() {
+            self.out.write_str(if *b { "true" } else { "false" })?;
+            return Ok(());
+        }
+        if value.as_any().is::<Null>() || value.as_any().is::<()>() {
+            self.out.write_str("null")?;
+            return Ok(());
+        }
+        if let Some(s) = value.downcast_ref::<String>() {
+            self.write_str(s)?;
+            return Ok(());
+        }
+        if let Some(s) = value.downcast_ref::<&str>() {
+            self.write_str(s)?;
+            return Ok(());
+        }
+        if let Some(c) = value.downcast_ref::<char>() {
+            self.write_str(c.encode_utf8(&mut [0; 4]))?;
+            return Ok(());
+        }
+        write_integers!(
+            self, value, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+        );
+        write_floats!(self, value, f32, f64);
+        Err(JsonError::Unsupported { path: self.path() }.into())
+    }
+
+    fn write_array(&mut self, array: &'a [Box<dyn AnyType>]) -> Result<(), WriteError> {
+        self.enter()?;
+        if array.is_empty() {
+            self.out.write_str("[]")?;
+            return Ok(());
+        }
+        self.out.write_char('[')?;
+        for (i, value) in array.iter().enumerate() {
+            if i > 0 {
+                self.out.write_char(',')?;
+            }
+            self.path.push(Segment::Index(i));
+            self.newline()?;
+            self.write_value(&**value)?;
+            self.path.pop();
+        }
+        self.newline()?;
+        self.out.write_char(']')?;
+        Ok(())
+    }
+
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_char('"')?;
+        for c in s.chars() {
+            match c {
+                '"' => self.out.write_str("\\\"")?,
+                '\\' => self.out.write_str("\\\\")?,
+                '\n' => self.out.write_str("\\n")?,
+                '\r' => self.out.write_str("\\r")?,
+                '\t' => self.out.write_str("\\t")?,
+                '\u{08}' => self.out.write_str("\\b")?,
+                '\u{0c}' => self.out.write_str("\\f")?,
+                c if (c as u32) < 0x20 => write!(self.out, "\\u{:04x}", c as u32)?,
+                c => self.out.write_char(c)?,
+            }
+        }
+        self.out.write_char('"')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, PartialOrd)]
+    struct Foo;
+
+    #[test]
+    fn writes_compact_json() {
+        let obj = object!({
+            a: 1u8,
+            b: -2i64,
+            c: 1.0f64,
+            d: true,
+            e: (),
+            f: {
+                g: "h",
+            },
+            i: {},
+        });
+        assert_eq!(
+            obj.to_json_string(false).unwrap(),
+            r#"{"a":1,"b":-2,"c":1.0,"d":true,"e":null,"f":{"g":"h"},"i":{}}"#
+        );
+    }
+
+    #[test]
+    fn writes_pretty_json() {
+        let obj = object!({
+            a: 1,
+            b: {
+                c: "d",
+            },
+        });
+        assert_eq!(
+            obj.to_json_string(true).unwrap(),
+            "{\n  \"a\": 1,\n  \"b\": {\n    \"c\": \"d\"\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn writes_arrays() {
+        let obj = object!({
+            a: vec![Box::new(1) as Box<dyn AnyType>, Box::new("b")],
+            e: Vec::<Box<dyn AnyType>>::new(),
+        });
+        assert_eq!(
+            obj.to_json_string(false).unwrap(),
+            r#"{"a":[1,"b"],"e":[]}"#
+        );
+        assert_eq!(
+            obj.to_json_string(true).unwrap(),
+            "{\n  \"a\": [\n    1,\n    \"b\"\n  ],\n  \"e\": []\n}"
+        );
+    }
+
+    #[test]
+    fn transforms_keys() {
+        let obj = object!({
+            a: 1,
+            b: {
+                c: "c",
+            },
+        });
+        let options = SerializeOptions {
+            pretty: true,
+            key_transform: Some(|key| format!("{}\"", key.to_uppercase())),
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            obj.serialize_with(&options).unwrap(),
+            "{\n  \"A\\\"\": 1,\n  \"B\\\"\": {\n    \"C\\\"\": \"c\"\n  }\n}"
+        );
+        assert!(obj.contains_key("a"));
+    }
+
+    #[test]
+    fn writes_to_any_sink() {
+        let obj = object!({
+            list: vec![Box::new(1.0) as Box<dyn AnyType>, Box::new(-2i128), Box::new(Null)],
+            nested: {
+                text: "a\"b",
+                float: 2.5f32,
+            },
+        });
+        let mut out = String::new();
+        obj.write_json(&mut out).unwrap();
+        assert_eq!(out, obj.to_json_string(false).unwrap());
+        assert_eq!(
+            out,
+            r#"{"list":[1.0,-2,null],"nested":{"float":2.5,"text":"a\"b"}}"#
+        );
+
+        let bad = object!({ list: vec![Box::new(f64::NAN) as Box<dyn AnyType>], });
+        assert_eq!(bad.write_json(&mut String::new()), Err(fmt::Error));
+        assert_eq!(
+            bad.to_json_string(false),
+            Err(JsonError::NonFinite {
+                path: "list.0".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn limits_depth() {
+        let obj = object!({
+            a: {
+                b: vec![Box::new(object!({})) as Box<dyn AnyType>],
+            },
+        });
+        let options = |max_depth| SerializeOptions {
+            max_depth,
+            ..SerializeOptions::default()
+        };
+        assert_eq!(
+            obj.serialize_with(&options(4)).unwrap(),
+            r#"{"a":{"b":[{}]}}"#
+        );
+        assert_eq!(
+            obj.serialize_with(&options(3)),
+            Err(JsonError::TooDeep {
+                path: "a.b.0".to_string()
+            })
+        );
+        assert_eq!(
+            obj.serialize_with(&options(0)),
+            Err(JsonError::TooDeep {
+                path: String::new()
+            })
+        );
+
+        let mut deep = Object::new();
+        for _ in 0..Object::DEFAULT_MAX_DEPTH {
+            deep = Object::new().with("a", deep);
+        }
+        assert!(matches!(
+            deep.to_json_string(false),
+            Err(JsonError::TooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn escapes_strings() {
+        let obj = object!({
+            s: String::from("\"quoted\"\n\\\u{01}"),
+        });
+        assert_eq!(
+            obj.to_json_string(false).unwrap(),
+            r#"{"s":"\"quoted\"\n\\\u0001"}"#
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_values() {
+        let obj = object!({
+            a: {
+                b: Foo,
+            },
+        });
+        assert_eq!(
+            obj.to_json_string(false),
+            Err(JsonError::Unsupported {
+                path: "a.b".to_string()
+            })
+        );
+
+        let obj = object!({
+            a: vec![Box::new(Foo) as Box<dyn AnyType>],
+        });
+        assert_eq!(
+            obj.to_json_string(false),
+            Err(JsonError::Unsupported {
+                path: "a.0".to_string()
+            })
+        );
+
+        let obj = object!({
+            nan: f64::NAN,
+        });
+        assert_eq!(
+            obj.to_json_string(false),
+            Err(JsonError::NonFinite {
+                path: "nan".to_string()
+            })
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn applies_json_merge_patch() {
+        let mut obj = object!({
+            a: "b",
+            c: {
+                d: "e",
+                f: "g",
+            },
+            h: 1,
+        });
+        let patch = serde_json::json!({
+            "a": "z",
+            "c": { "f": null },
+            "h": { "i": 2, "j": null },
+            "k": [1, null, -1, 1.5],
+            "missing": null,
+        });
+        obj.apply_json_patch(&patch).unwrap();
+
+        assert_eq!(obj.get_as::<String>("a"), Some(&"z".to_string()));
+        let c = obj.get_as::<Object>("c").unwrap();
+        assert_eq!(c.get_as::<&str>("d"), Some(&"e"));
+        assert!(c.get("f").is_none());
+        let h = obj.get_as::<Object>("h").unwrap();
+        assert_eq!(h.get_as::<i64>("i"), Some(&2));
+        assert!(h.get("j").is_none());
+        assert!(obj.get("missing").is_none());
+        let k = obj.get_as::<Vec<Box<dyn AnyType>>>("k").unwrap();
+        assert_eq!(k[1].downcast_ref::<Null>(), Some(&Null));
+        assert_eq!(
+            obj.to_json_string(false).unwrap(),
+            r#"{"a":"z","c":{"d":"e"},"h":{"i":2},"k":[1,null,-1,1.5]}"#
+        );
+
+        assert_eq!(
+            obj.apply_json_patch(&serde_json::json!([])),
+            Err(JsonError::NotAnObject)
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_merge_patch_keeps_nulls_inside_arrays() {
+        let mut obj = object!({});
+        let patch = serde_json::json!({ "k": [{ "a": null, "b": { "c": null } }] });
+        obj.apply_json_patch(&patch).unwrap();
+        assert_eq!(
+            obj.to_json_string(false).unwrap(),
+            r#"{"k":[{"a":null,"b":{"c":null}}]}"#
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_merge_patch_respects_seal() {
+        let mut obj = object!({ a: 1, b: 2 });
+        obj.seal();
+        let patch = serde_json::json!({ "a": "x", "b": null, "c": 3, "d": { "e": 4 } });
+        obj.apply_json_patch(&patch).unwrap();
+        assert_eq!(obj.to_json_string(false).unwrap(), r#"{"a":"x"}"#);
+    }
+}
@@ -16,6 +16,7 @@
 //! - Dynamic key-value map
 //! - Type checking at runtime
 //! - Macro for easy and intuitive object creation
+//! - JSON serialization
 //!
 //! ## Usage
 //!
@@ -62,6 +63,13 @@
 //! assert_eq!(obj.get_as::<&str>("key1"), Some(&"value1"));
 //! ```
 //!
+//! ## Optional Features
+//!
+//! - `serde`: serialize and deserialize `Object` with `serde`, tagging every value with its type.
+//! - `serde_json`: apply JSON Merge Patches with `Object::apply_json_patch`.
+//! - `toml`: convert between `Object` and `toml::Table`.
+//! - `yaml`: convert between `Object` and `serde_yaml::Mapping`.
+//!
 //! ## Repository
 //!
 //! The source code for `dynamic_object` is available on GitHub at [github.com/trvswgnr/dynamic_object](https://github.com/trvswgnr/dynamic_object).
@@ -75,12 +83,60 @@
 //! `dynamic_object` is licensed under the MIT license. Please see the `LICENSE` file in the GitHub repository for more information.
 
 use std::{
-    any::Any,
+    any::{self, Any, TypeId},
+    borrow::Cow,
     cmp::Ordering,
-    collections::BTreeMap,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap},
     fmt::{self, Debug, Formatter},
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
+
+mod array;
+mod checkpoint;
+mod coercion;
+mod debug;
+mod defaults;
+mod entry;
+mod env;
+mod error;
+mod iter;
+mod json;
+mod merge;
+mod numeric;
+mod observable;
+mod patch;
+mod pretty;
+mod registry;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "toml")]
+mod toml;
+mod total;
+mod typed;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub use array::{Array, ArrayExt};
+pub use checkpoint::Checkpointed;
+pub use coercion::CoercionTable;
+pub use defaults::WithDefaults;
+pub use entry::{OccupiedEntry, TypedEntry, VacantEntry};
+pub use error::{
+    AccessError, ConversionError, InsertError, KeyCollision, PathError, SchemaError, TypeConflict,
 };
+pub use iter::ObjectIterExt;
+pub use json::{JsonError, SerializeOptions};
+pub use merge::{ArrayMerge, MergeOptions, ObjectMerge};
+pub use observable::{ChangeEvent, ObservableObject};
+pub use patch::{ObjectPatch, PatchOp};
+pub use pretty::PrettyOptions;
+pub use registry::register_trait;
+#[cfg(feature = "serde")]
+pub use serde::MaxDepth;
+pub use total::TotalObject;
+#[doc(hidden)]
+pub use typed::private as __private;
+pub use typed::{Key, Schema, TypedObject};
 
 /// Creates a new `Object`.
 ///
@@ -109,6 +165,54 @@ use std::{
 ///   The keys must be identifiers, and the values can be any expression.
 ///   If a value is surrounded by `{}` it is treated as another `Object`.
 ///
+/// Any entry can be followed by `if condition` to only insert it when the condition is `true`:
+///
+/// ```
+/// use dynamic_object::object;
+/// let verbose = false;
+/// let obj = object!({
+///     name: "app",
+///     level: "debug" if verbose,
+///     extra: {
+///         inner: 1,
+///     } if !verbose,
+/// });
+/// assert!(obj.get("level").is_none());
+/// assert!(obj.get("extra").is_some());
+/// ```
+///
+/// A conditional value that itself contains `if` must be wrapped in parentheses.
+///
+/// The comma after the last entry is optional, and an entry written as a bare identifier inserts
+/// the variable of the same name, like struct field shorthand:
+///
+/// ```
+/// use dynamic_object::object;
+/// let host = "localhost";
+/// let port = 8080;
+/// let obj = object!({ host, port, tls: { enabled: false } });
+/// assert_eq!(obj.get_as::<&str>("host"), Some(&"localhost"));
+/// assert_eq!(obj.get_as::<i32>("port"), Some(&8080));
+/// ```
+///
+/// A value written as `@build closure` is a nested `Object` built imperatively: the closure
+/// receives a fresh `&mut Object` to populate, as with [`Object::build`].
+///
+/// ```
+/// use dynamic_object::{object, Object};
+/// let ports = [80, 443];
+/// let obj = object!({
+///     name: "app",
+///     ports: @build |o| {
+///         for port in ports {
+///             o.insert(format!("p{port}"), port);
+///         }
+///     },
+/// });
+/// let ports = obj.get_as::<Object>("ports").unwrap();
+/// assert_eq!(ports.get_as::<i32>("p443"), Some(&443));
+/// ```
+///
 /// # Representation
 ///
 /// The `object` macro is expanded to a series of `insert` calls on a new `Object`. The keys are
@@ -119,23 +223,111 @@ macro_rules! object {
         $crate::Object::new()
     };
     ({
-        $key:ident: { $($inner:tt)* }, $($rest:tt)*
+        $key:ident: { $($inner:tt)* } if $cond:expr $(, $($rest:tt)*)?
+    }) => {
+        {
+            let mut map = $crate::object!({ $($($rest)*)? });
+            if $cond {
+                map.insert(stringify!($key), $crate::object!({ $($inner)* }));
+            }
+            map
+        }
+    };
+    ({
+        $key:ident: { $($inner:tt)* } $(, $($rest:tt)*)?
     }) => {
         {
-            let mut map = object!({ $($rest)* });
+            let mut map = $crate::object!({ $($($rest)*)? });
             map.insert(stringify!($key), $crate::object!({ $($inner)* }));
             map
         }
     };
     ({
-        $key:ident: $value:expr, $($rest:tt)*
+        $key:ident: @build $builder:expr $(, $($rest:tt)*)?
+    }) => {
+        {
+            let mut map = $crate::object!({ $($($rest)*)? });
+            map.insert(stringify!($key), $crate::Object::build($builder));
+            map
+        }
+    };
+    ({
+        $key:ident: $value:expr $(, $($rest:tt)*)?
     }) => {
         {
-            let mut map = $crate::object!({ $($rest)* });
+            let mut map = $crate::object!({ $($($rest)*)? });
             map.insert(stringify!($key), $value);
             map
         }
     };
+    ({
+        $key:ident $(, $($rest:tt)*)?
+    }) => {
+        {
+            let mut map = $crate::object!({ $($($rest)*)? });
+            map.insert(stringify!($key), $key);
+            map
+        }
+    };
+    ({
+        $key:ident: $($tail:tt)*
+    }) => {
+        $crate::object!(@if $key [] $($tail)*)
+    };
+    (@if $key:ident [$($value:tt)+] if $cond:expr $(, $($rest:tt)*)?) => {
+        {
+            let mut map = $crate::object!({ $($($rest)*)? });
+            if $cond {
+                map.insert(stringify!($key), $($value)+);
+            }
+            map
+        }
+    };
+    (@if $key:ident [$($value:tt)*] $next:tt $($tail:tt)*) => {
+        $crate::object!(@if $key [$($value)* $next] $($tail)*)
+    };
+}
+
+/// Reads a value at a statically known path of nested objects.
+///
+/// `object_path!(obj, a.b.c as T)` expands to a chain of `get_as` calls: `a` and `b` must hold
+/// nested `Object`s, and the result is `Some(&T)` if `c` holds a `T`, or `None` if any step fails.
+/// The segments must be identifiers. `obj` can be anything that dereferences to an `Object`.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{object, object_path};
+/// let config = object!({
+///     server: {
+///         http: {
+///             port: 8080,
+///         },
+///     },
+/// });
+/// assert_eq!(object_path!(config, server.http.port as i32), Some(&8080));
+/// assert_eq!(object_path!(&config, server.http.port as i64), None);
+/// assert_eq!(object_path!(config, server.https.port as i32), None);
+/// ```
+#[macro_export]
+macro_rules! object_path {
+    (@walk $current:expr; $last:ident as $ty:ty) => {
+        $current.and_then(|object: &$crate::Object| object.get_as::<$ty>(stringify!($last)))
+    };
+    (@walk $current:expr; $segment:ident . $($rest:ident).+ as $ty:ty) => {
+        $crate::object_path!(
+            @walk $current.and_then(|object: &$crate::Object| {
+                object.get_as::<$crate::Object>(stringify!($segment))
+            });
+            $($rest).+ as $ty
+        )
+    };
+    ($object:expr, $($segment:ident).+ as $ty:ty) => {
+        {
+            let object: &$crate::Object = &$object;
+            $crate::object_path!(@walk ::core::option::Option::Some(object); $($segment).+ as $ty)
+        }
+    };
 }
 
 /// A type-erased value.
@@ -146,11 +338,34 @@ pub trait AnyType: Any {
     /// Upcast to `Any` mutably.
     fn as_any_mut(&mut self) -> &mut dyn Any;
 
+    /// Upcast a boxed value to a boxed `Any`.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
     /// Compare with another type-erased value.
     fn dyn_cmp(&self, other: &dyn AnyType) -> Option<Ordering>;
 
+    /// Compare with another type-erased value, comparing numbers by value across types.
+    ///
+    /// If both values are of primitive numeric types, they are compared as numbers, so `1i32`
+    /// equals `1i64` and `1u8` is less than `1.5f64`. Two integers are compared exactly; if
+    /// either value is a float, both are converted to `f64` first, which may round integers above
+    /// 2<sup>53</sup>. Any other values are compared with [`AnyType::dyn_cmp`], and values nested
+    /// in objects are not compared numerically.
+    fn dyn_cmp_numeric(&self, other: &dyn AnyType) -> Option<Ordering>;
+
+    /// Compare with another type-erased value, always producing an ordering.
+    ///
+    /// See [`Object::cmp_total`] for the rules used.
+    fn dyn_total_cmp(&self, other: &dyn AnyType) -> Ordering;
+
     /// Write the `Debug` representation.
     fn dyn_debug(&self, f: &mut Formatter<'_>) -> fmt::Result;
+
+    /// Returns the name of the concrete type.
+    fn dyn_type_name(&self) -> &'static str;
+
+    /// Returns the `TypeId` of the concrete type.
+    fn dyn_type_id(&self) -> TypeId;
 }
 
 impl<T: Any + Debug + PartialOrd> AnyType for T {
@@ -162,6 +377,10 @@ impl<T: Any + Debug + PartialOrd> AnyType for T {
         self
     }
 
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
     fn dyn_cmp(&self, other: &dyn AnyType) -> Option<Ordering> {
         other
             .as_any()
@@ -169,9 +388,65 @@ impl<T: Any + Debug + PartialOrd> AnyType for T {
             .and_then(|other| self.partial_cmp(other))
     }
 
+    fn dyn_cmp_numeric(&self, other: &dyn AnyType) -> Option<Ordering> {
+        if let Some(ordering) = numeric::cmp_integers(self, other) {
+            return Some(ordering);
+        }
+        match (numeric::to_f64(self), numeric::to_f64(other)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => self.dyn_cmp(other),
+        }
+    }
+
+    fn dyn_total_cmp(&self, other: &dyn AnyType) -> Ordering {
+        let Some(other) = other.as_any().downcast_ref::<T>() else {
+            return any::type_name::<T>()
+                .cmp(other.dyn_type_name())
+                .then_with(|| TypeId::of::<T>().cmp(&other.as_any().type_id()));
+        };
+        let (this, that) = (self.as_any(), other.as_any());
+        if let (Some(a), Some(b)) = (this.downcast_ref::<Object>(), that.downcast_ref::<Object>()) {
+            return a.cmp_total(b);
+        }
+        if let (Some(a), Some(b)) = (this.downcast_ref::<f64>(), that.downcast_ref::<f64>()) {
+            return a.total_cmp(b);
+        }
+        if let (Some(a), Some(b)) = (this.downcast_ref::<f32>(), that.downcast_ref::<f32>()) {
+            return a.total_cmp(b);
+        }
+        if let (Some(a), Some(b)) = (
+            this.downcast_ref::<Vec<Box<dyn AnyType>>>(),
+            that.downcast_ref::<Vec<Box<dyn AnyType>>>(),
+        ) {
+            return a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| (**a).dyn_total_cmp(&**b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len()));
+        }
+        // Values that aren't comparable with themselves, like containers of `NaN`, sort after the
+        // rest and are ordered by their `Debug` representation, which keeps the order transitive.
+        let debug_cmp = || format!("{self:?}").cmp(&format!("{other:?}"));
+        match (self.partial_cmp(self), other.partial_cmp(other)) {
+            (Some(_), Some(_)) => self.partial_cmp(other).unwrap_or_else(debug_cmp),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => debug_cmp(),
+        }
+    }
+
     fn dyn_debug(&self, f: &mut Formatter<'_>) -> fmt::Result {
         Debug::fmt(self, f)
     }
+
+    fn dyn_type_name(&self) -> &'static str {
+        any::type_name::<T>()
+    }
+
+    fn dyn_type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
 }
 
 impl dyn AnyType + '_ {
@@ -184,6 +459,71 @@ impl dyn AnyType + '_ {
     pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
         self.as_any_mut().downcast_mut::<T>()
     }
+
+    /// Returns `true` if the value is of type `T`, like [`Any::is`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({ count: 1 });
+    /// assert_eq!(obj.get("count").map(|v| v.is::<i32>()), Some(true));
+    /// assert_eq!(obj.get("count").map(|v| v.is::<i64>()), Some(false));
+    /// ```
+    pub fn is<T: 'static>(&self) -> bool {
+        self.as_any().is::<T>()
+    }
+
+    /// Returns `true` if the value is an `Object`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({ nested: {}, count: 1 });
+    /// assert!(obj.get("nested").is_some_and(|v| v.is_object()));
+    /// assert!(!obj.get("count").is_some_and(|v| v.is_object()));
+    /// ```
+    pub fn is_object(&self) -> bool {
+        self.is::<Object>()
+    }
+
+    /// Returns the value as an `Object`, if it is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({
+    ///     nested: {
+    ///         key: "value",
+    ///     },
+    /// });
+    /// let nested = obj.get("nested").and_then(|v| v.as_object());
+    /// assert_eq!(nested.and_then(|o| o.get_as::<&str>("key")), Some(&"value"));
+    /// ```
+    pub fn as_object(&self) -> Option<&Object> {
+        self.downcast_ref::<Object>()
+    }
+
+    /// Returns the value as a mutable `Object`, if it is one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut obj = object!({
+    ///     nested: {},
+    /// });
+    /// if let Some(nested) = obj.get_mut("nested").and_then(|v| v.as_object_mut()) {
+    ///     nested.insert("key", "value");
+    /// }
+    /// let nested = obj.get("nested").and_then(|v| v.as_object());
+    /// assert_eq!(nested.and_then(|o| o.get_as::<&str>("key")), Some(&"value"));
+    /// ```
+    pub fn as_object_mut(&mut self) -> Option<&mut Object> {
+        self.downcast_mut::<Object>()
+    }
 }
 
 impl PartialOrd for dyn AnyType + '_ {
@@ -204,6 +544,24 @@ impl Debug for dyn AnyType + '_ {
     }
 }
 
+/// An explicit null value.
+///
+/// `Null` lets an `Object` tell "this key is unset" apart from "this key is absent", for example
+/// when merging layered configs with [`Object::merge_preferring_non_null`]. JSON and YAML `null`
+/// values are converted to `Null`, and [`Object::is_null`] and [`Object::get_optional_as`] treat
+/// it as an explicit "no value".
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{Null, Object};
+/// let mut object = Object::new();
+/// object.insert("key", Null);
+/// assert_eq!(object.get_as::<Null>("key"), Some(&Null));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Null;
+
 /// A type-erased key-value map.
 ///
 /// The `Object` struct is a wrapper around a `BTreeMap` that allows storing any value that implements the `Any` trait.
@@ -239,12 +597,27 @@ impl Debug for dyn AnyType + '_ {
 /// [Any]: core::any::Any "any::Any"
 /// [AnyType]: crate::AnyType "AnyType"
 /// [BTreeMap]: std::collections::BTreeMap "collections::BTreeMap"
-#[derive(Default, Debug, PartialEq, PartialOrd)]
+#[derive(Default)]
 pub struct Object {
     map: BTreeMap<String, Box<dyn AnyType>>,
+    sealed: bool,
 }
 
 impl Object {
+    /// The default limit on the nesting depth of an `Object` being serialized, as measured by
+    /// [`Object::max_depth`].
+    ///
+    /// Serializing a deeper `Object` fails instead of risking a stack overflow. See
+    /// [`SerializeOptions::max_depth`] for JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// assert!(object!({ a: { b: 1, }, }).max_depth() <= Object::DEFAULT_MAX_DEPTH);
+    /// ```
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+
     /// Creates a new `Object`.
     ///
     /// # Examples
@@ -257,6 +630,46 @@ impl Object {
         Self::default()
     }
 
+    /// Creates a new `Object` and populates it with `f`.
+    ///
+    /// This is also what the `@build` form of the [`object!`] macro expands to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let object = Object::build(|o| {
+    ///     for i in 0..3 {
+    ///         o.insert(i.to_string(), i);
+    ///     }
+    /// });
+    /// assert_eq!(object.len(), 3);
+    /// ```
+    pub fn build(f: impl FnOnce(&mut Object)) -> Self {
+        let mut object = Object::new();
+        f(&mut object);
+        object
+    }
+
+    /// Creates an `Object` from a map of already boxed values, storing them as they are.
+    ///
+    /// This is the counterpart of the map an `Object` derefs to. Converting such a map with `From`
+    /// would box every value a second time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use dynamic_object::{AnyType, Object};
+    /// let mut map = BTreeMap::new();
+    /// map.insert("a".to_string(), Box::new(1) as Box<dyn AnyType>);
+    /// let object = Object::from_boxed_map(map);
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&1));
+    /// ```
+    pub fn from_boxed_map(map: BTreeMap<String, Box<dyn AnyType>>) -> Self {
+        Object::from_map(map)
+    }
+
     /// Inserts a key-value pair into the `Object`.
     ///
     /// # Examples
@@ -267,7 +680,180 @@ impl Object {
     /// object.insert("key", "value");
     /// ```
     pub fn insert<K: Into<String>, V: AnyType>(&mut self, key: K, value: V) {
-        self.map.insert(key.into(), Box::new(value));
+        self.try_insert(key, value);
+    }
+
+    /// Inserts a key-value pair into the `Object`, returning whether it was stored.
+    ///
+    /// This only returns `false` when the `Object` is [sealed](Object::seal) and the key is new.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// assert!(object.try_insert("key", "value"));
+    /// object.seal();
+    /// assert!(object.try_insert("key", "other"));
+    /// assert!(!object.try_insert("new", "value"));
+    /// ```
+    pub fn try_insert<K: Into<String>, V: AnyType>(&mut self, key: K, value: V) -> bool {
+        match self.map.entry(key.into()) {
+            Entry::Occupied(mut entry) => {
+                entry.insert(Box::new(value));
+                true
+            }
+            Entry::Vacant(_) if self.sealed => false,
+            Entry::Vacant(entry) => {
+                entry.insert(Box::new(value));
+                true
+            }
+        }
+    }
+
+    /// Seals the key set of the `Object`.
+    ///
+    /// Once sealed, existing values can still be replaced or mutated in place, e.g. with
+    /// [`Object::get_mut_as`], but methods that would add a key don't:
+    ///
+    /// - [`Object::insert`] and the methods built on it, like [`Object::with`], [`Object::set`]
+    ///   and [`Object::insert_owned`], as well as the merges and [`Object::apply_patch`], skip
+    ///   keys that aren't already present. [`Object::try_insert`] returns `false` for them, and
+    ///   [`Object::insert_all`] reports them as [`InsertOutcome::Rejected`].
+    /// - [`Object::get_or_insert_as`] and [`Object::get_or_try_insert_with_as`] return `None`.
+    /// - [`Object::try_get_or_insert_as`], [`Object::entry_ref_as`] and
+    ///   [`Object::insert_checked`] return [`InsertError::Sealed`], and
+    ///   [`Object::path_entry_as`] returns [`PathError::Sealed`].
+    ///
+    /// The exceptions are [`Object::mut_or_replace_as`], [`Object::insert_object`] and
+    /// [`Object::typed_entry`], which always hand out a reference to the value, methods that
+    /// rename keys, and direct access to the map through `DerefMut`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut settings = object!({
+    ///     volume: 5,
+    /// });
+    /// settings.seal();
+    /// *settings.get_mut_as::<i32>("volume").unwrap() += 1;
+    /// settings.insert("volum", 0);
+    /// assert_eq!(settings.get_as::<i32>("volume"), Some(&6));
+    /// assert!(!settings.contains_key("volum"));
+    /// ```
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    /// Lifts a previous [`Object::seal`], allowing new keys again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.seal();
+    /// object.unseal();
+    /// object.insert("key", "value");
+    /// assert!(object.contains_key("key"));
+    /// ```
+    pub fn unseal(&mut self) {
+        self.sealed = false;
+    }
+
+    /// Returns `true` if the `Object` is [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// assert!(!object.is_sealed());
+    /// object.seal();
+    /// assert!(object.is_sealed());
+    /// ```
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+
+    /// Inserts a key-value pair and returns the `Object`, for builder-style construction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let object = Object::new().with("a", 1).with("b", "two");
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&1));
+    /// assert_eq!(object.get_as::<&str>("b"), Some(&"two"));
+    /// ```
+    pub fn with<K: Into<String>, V: AnyType>(mut self, key: K, value: V) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Inserts a key-value pair and returns a mutable reference to the `Object`, so calls can be
+    /// chained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.set("a", 1).set("b", "two");
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&1));
+    /// assert_eq!(object.get_as::<&str>("b"), Some(&"two"));
+    /// ```
+    pub fn set<K: Into<String>, V: AnyType>(&mut self, key: K, value: V) -> &mut Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Inserts every key-value pair from `pairs`, reporting what happened to each key.
+    ///
+    /// The returned vector lists the keys in the order they were inserted, each with an
+    /// `InsertOutcome` noting whether the key was fresh or an existing value was overwritten, or
+    /// whether the key was rejected because the `Object` is [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{InsertOutcome, Object};
+    /// let mut object = Object::new();
+    /// object.insert("a", 0);
+    /// let outcomes = object.insert_all([("a", 1), ("b", 2)]);
+    /// assert_eq!(
+    ///     outcomes,
+    ///     vec![
+    ///         ("a".to_string(), InsertOutcome::Overwritten),
+    ///         ("b".to_string(), InsertOutcome::Inserted),
+    ///     ]
+    /// );
+    /// ```
+    pub fn insert_all<I, K, V>(&mut self, pairs: I) -> Vec<(String, InsertOutcome)>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: AnyType,
+    {
+        pairs
+            .into_iter()
+            .map(|(key, value)| {
+                let key = key.into();
+                let outcome = match self.map.entry(key.clone()) {
+                    Entry::Occupied(mut entry) => {
+                        entry.insert(Box::new(value));
+                        InsertOutcome::Overwritten
+                    }
+                    Entry::Vacant(_) if self.sealed => InsertOutcome::Rejected,
+                    Entry::Vacant(entry) => {
+                        entry.insert(Box::new(value));
+                        InsertOutcome::Inserted
+                    }
+                };
+                (key, outcome)
+            })
+            .collect()
     }
 
     /// Returns a reference to the value corresponding to the key if it is of type `T`, or `None` if it isn't.
@@ -289,320 +875,3918 @@ impl Object {
             .and_then(|v| (**v).as_any().downcast_ref::<T>())
     }
 
-    /// Returns a reference to the value corresponding to the key if it is of type `T`, or inserts it if it doesn't exist.
-    ///
-    /// If the value is not of type `T`, this method will return `None`.
+    /// Returns a mutable reference to the value corresponding to the key if it is of type `T`, or
+    /// `None` if it isn't.
     ///
     /// # Examples
     ///
     /// ```
     /// use dynamic_object::Object;
     /// let mut object = Object::new();
-    /// let value = object.get_or_insert_as("key", "value");
-    /// assert_eq!(value, Some(&mut "value"));
+    /// object.insert("key", 1);
+    /// *object.get_mut_as::<i32>("key").unwrap() += 1;
+    /// assert_eq!(object.get_as::<i32>("key"), Some(&2));
+    /// assert!(object.get_mut_as::<&str>("key").is_none());
     /// ```
-    pub fn get_or_insert_as<T: AnyType>(
-        &mut self,
-        key: impl Into<String>,
-        value: T,
-    ) -> Option<&mut T> {
-        let bx = self
-            .map
-            .entry(key.into())
-            .or_insert_with(|| Box::new(value));
-
-        (**bx).as_any_mut().downcast_mut::<T>()
-    }
-}
-
-impl Deref for Object {
-    type Target = BTreeMap<String, Box<dyn AnyType>>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.map
+    pub fn get_mut_as<T: 'static>(&mut self, key: &str) -> Option<&mut T> {
+        self.map
+            .get_mut(key)
+            .and_then(|v| (**v).as_any_mut().downcast_mut::<T>())
     }
-}
 
-impl DerefMut for Object {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.map
+    /// Returns the value corresponding to the key as a `Cow::Borrowed` if it is of type `T`, or
+    /// `None` if it isn't.
+    ///
+    /// The value is never cloned here; callers that need ownership can call `into_owned` on the
+    /// result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert("key", "value".to_string());
+    /// let value = object.get_as_cow::<String>("key").unwrap();
+    /// assert!(matches!(value, Cow::Borrowed(_)));
+    /// assert_eq!(value.into_owned(), "value");
+    /// ```
+    pub fn get_as_cow<T: Clone + 'static>(&self, key: &str) -> Option<Cow<'_, T>> {
+        self.get_as::<T>(key).map(Cow::Borrowed)
     }
-}
 
-#[cfg(test)]
+    /// Returns the value corresponding to the key as a slice if it is a `Vec<T>`, or `None` if it isn't.
+    ///
+    /// This is a shorthand for `get_as::<Vec<T>>(key).map(Vec::as_slice)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert("key", vec![1, 2, 3]);
+    /// assert_eq!(object.get_as_slice::<i32>("key"), Some(&[1, 2, 3][..]));
+    /// ```
+    pub fn get_as_slice<T: 'static>(&self, key: &str) -> Option<&[T]> {
+        self.get_as::<Vec<T>>(key).map(Vec::as_slice)
+    }
+
+    /// Returns the value corresponding to the key as a mutable slice if it is a `Vec<T>`, or `None` if it isn't.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert("key", vec![1, 2, 3]);
+    /// if let Some(slice) = object.get_as_slice_mut::<i32>("key") {
+    ///     slice.reverse();
+    /// }
+    /// assert_eq!(object.get_as_slice::<i32>("key"), Some(&[3, 2, 1][..]));
+    /// ```
+    pub fn get_as_slice_mut<T: 'static>(&mut self, key: &str) -> Option<&mut [T]> {
+        self.map
+            .get_mut(key)
+            .and_then(|v| (**v).as_any_mut().downcast_mut::<Vec<T>>())
+            .map(Vec::as_mut_slice)
+    }
+
+    /// Returns a reference to the value corresponding to the key if it is of type `T`, or inserts it if it doesn't exist.
+    ///
+    /// If the key already holds a value that is not of type `T`, this method will return `None` and leave the
+    /// existing value untouched. Use [`Object::try_get_or_insert_as`] to tell this case apart. It
+    /// also returns `None` if the key is absent and the `Object` is [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// let value = object.get_or_insert_as("key", "value");
+    /// assert_eq!(value, Some(&mut "value"));
+    /// ```
+    pub fn get_or_insert_as<T: AnyType>(
+        &mut self,
+        key: impl Into<String>,
+        value: T,
+    ) -> Option<&mut T> {
+        let bx = match self.map.entry(key.into()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(_) if self.sealed => return None,
+            Entry::Vacant(entry) => entry.insert(Box::new(value)),
+        };
+        (**bx).as_any_mut().downcast_mut::<T>()
+    }
+
+    /// Returns a reference to the value corresponding to the key if it is of type `T`, or inserts it if it doesn't exist.
+    ///
+    /// Unlike [`Object::get_or_insert_as`], this method reports whether the value was inserted or found,
+    /// and returns an [`InsertError::TypeConflict`] if the key already holds a value of another
+    /// type. The existing value is never replaced. If the key is absent and the `Object` is
+    /// [sealed](Object::seal), [`InsertError::Sealed`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{GetOrInsert, InsertError, Object};
+    /// let mut object = Object::new();
+    /// let value = object.try_get_or_insert_as("key", 1);
+    /// assert!(matches!(value, Ok(GetOrInsert::Inserted(&mut 1))));
+    /// let value = object.try_get_or_insert_as("key", 2);
+    /// assert!(matches!(value, Ok(GetOrInsert::Found(&mut 1))));
+    /// let value = object.try_get_or_insert_as("key", "value");
+    /// assert!(matches!(value, Err(InsertError::TypeConflict(conflict)) if conflict.found == "i32"));
+    /// ```
+    pub fn try_get_or_insert_as<T: AnyType>(
+        &mut self,
+        key: impl Into<String>,
+        value: T,
+    ) -> Result<GetOrInsert<'_, T>, InsertError> {
+        match self.map.entry(key.into()) {
+            Entry::Vacant(entry) if self.sealed => Err(InsertError::Sealed {
+                key: entry.into_key(),
+            }),
+            Entry::Vacant(entry) => {
+                let bx = entry.insert(Box::new(value));
+                let value = (**bx)
+                    .as_any_mut()
+                    .downcast_mut::<T>()
+                    .expect("the value was just inserted");
+                Ok(GetOrInsert::Inserted(value))
+            }
+            Entry::Occupied(entry) => {
+                if !(**entry.get()).as_any().is::<T>() {
+                    return Err(InsertError::TypeConflict(TypeConflict {
+                        key: entry.key().clone(),
+                        expected: any::type_name::<T>(),
+                        found: (**entry.get()).dyn_type_name(),
+                    }));
+                }
+                let value = (**entry.into_mut())
+                    .as_any_mut()
+                    .downcast_mut::<T>()
+                    .expect("the value is of type `T`");
+                Ok(GetOrInsert::Found(value))
+            }
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key if it is of type `T`, or inserts
+    /// the result of `default` if it doesn't exist.
+    ///
+    /// This behaves like [`Object::try_get_or_insert_as`], but takes the key by reference and only
+    /// allocates a `String` for it when inserting, and only builds the default value then as well.
+    /// Prefer it in loops that mostly hit existing keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{GetOrInsert, Object};
+    /// let mut object = Object::new();
+    /// for word in ["a", "b", "a"] {
+    ///     *object.entry_ref_as(word, || 0).unwrap().into_mut() += 1;
+    /// }
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&2));
+    /// assert!(matches!(object.entry_ref_as("b", || 0), Ok(GetOrInsert::Found(&mut 1))));
+    /// ```
+    pub fn entry_ref_as<T: AnyType>(
+        &mut self,
+        key: &str,
+        default: impl FnOnce() -> T,
+    ) -> Result<GetOrInsert<'_, T>, InsertError> {
+        if !self.map.contains_key(key) {
+            if self.sealed {
+                return Err(InsertError::Sealed {
+                    key: key.to_string(),
+                });
+            }
+            let bx = self
+                .map
+                .entry(key.to_string())
+                .or_insert_with(|| Box::new(default()));
+            let value = (**bx)
+                .as_any_mut()
+                .downcast_mut::<T>()
+                .expect("the value was just inserted");
+            return Ok(GetOrInsert::Inserted(value));
+        }
+        let bx = self.map.get_mut(key).expect("the key is present");
+        let found = (**bx).dyn_type_name();
+        (**bx)
+            .as_any_mut()
+            .downcast_mut::<T>()
+            .map(GetOrInsert::Found)
+            .ok_or_else(|| {
+                InsertError::TypeConflict(TypeConflict {
+                    key: key.to_string(),
+                    expected: any::type_name::<T>(),
+                    found,
+                })
+            })
+    }
+
+    /// Merges `other` into the `Object`, calling `resolve` for keys present in both.
+    ///
+    /// `resolve` receives the key, the existing value and the incoming value, and returns the value to keep.
+    /// Keys present in only one of the objects are taken as-is, except that keys only in `other`
+    /// are skipped if the `Object` is [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut a = object!({
+    ///     hits: 1,
+    ///     name: "a",
+    /// });
+    /// let b = object!({
+    ///     hits: 2,
+    ///     extra: true,
+    /// });
+    /// a.merge_with(b, |_, ours, theirs| {
+    ///     if let (Some(x), Some(y)) = (ours.downcast_ref::<i32>(), theirs.downcast_ref::<i32>()) {
+    ///         return Box::new(x + y);
+    ///     }
+    ///     theirs
+    /// });
+    /// assert_eq!(a.get_as::<i32>("hits"), Some(&3));
+    /// assert_eq!(a.get_as::<&str>("name"), Some(&"a"));
+    /// assert_eq!(a.get_as::<bool>("extra"), Some(&true));
+    /// ```
+    pub fn merge_with(
+        &mut self,
+        other: Object,
+        mut resolve: impl FnMut(&str, Box<dyn AnyType>, Box<dyn AnyType>) -> Box<dyn AnyType>,
+    ) {
+        for (key, theirs) in other.map {
+            let value = match self.map.remove(&key) {
+                Some(ours) => resolve(&key, ours, theirs),
+                None if self.sealed => continue,
+                None => theirs,
+            };
+            self.map.insert(key, value);
+        }
+    }
+
+    /// Splits the `Object` into several objects, each holding the values of a single type.
+    ///
+    /// The returned map is keyed by type name, as given by [`AnyType::dyn_type_name`].
+    /// Values are moved, not cloned. Note that type names come from `std::any::type_name` and
+    /// are meant for diagnostics, so their exact form may differ between compiler versions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({
+    ///     a: 1,
+    ///     b: "two",
+    ///     c: 3,
+    /// });
+    /// let parts = obj.partition_by_type();
+    /// assert_eq!(parts["i32"].len(), 2);
+    /// assert_eq!(parts["&str"].get_as::<&str>("b"), Some(&"two"));
+    /// ```
+    pub fn partition_by_type(self) -> BTreeMap<&'static str, Object> {
+        let mut parts = BTreeMap::<_, Object>::new();
+        for (key, value) in self.map {
+            parts
+                .entry((*value).dyn_type_name())
+                .or_default()
+                .map
+                .insert(key, value);
+        }
+        parts
+    }
+
+    /// Renames keys according to `mapping`, which maps old names to new names.
+    ///
+    /// Keys that aren't in `mapping` are left alone, as are entries of `mapping` whose old name is absent.
+    /// If two keys would be renamed to the same name, or a key would be renamed onto a key that is kept,
+    /// a `KeyCollision` error is returned and the `Object` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use dynamic_object::object;
+    /// let mut config = object!({
+    ///     hostname: "localhost",
+    ///     port: 8080,
+    /// });
+    /// let mapping = BTreeMap::from([("hostname".to_string(), "host".to_string())]);
+    /// config.rename_keys(&mapping).unwrap();
+    /// assert_eq!(config.get_as::<&str>("host"), Some(&"localhost"));
+    /// assert!(config.get("hostname").is_none());
+    /// ```
+    pub fn rename_keys(&mut self, mapping: &BTreeMap<String, String>) -> Result<(), KeyCollision> {
+        let renames = mapping
+            .iter()
+            .filter(|(from, to)| from != to && self.map.contains_key(*from))
+            .collect::<Vec<_>>();
+
+        let mut targets = BTreeMap::new();
+        for (from, to) in &renames {
+            if targets.insert(*to, *from).is_some() {
+                return Err(KeyCollision {
+                    key: to.to_string(),
+                });
+            }
+        }
+        for to in targets.keys() {
+            if self.map.contains_key(*to) && !renames.iter().any(|(from, _)| from == to) {
+                return Err(KeyCollision {
+                    key: to.to_string(),
+                });
+            }
+        }
+
+        let moved = renames
+            .into_iter()
+            .filter_map(|(from, to)| Some((to.clone(), self.map.remove(from)?)))
+            .collect::<Vec<_>>();
+        self.map.extend(moved);
+        Ok(())
+    }
+
+    /// Returns `true` if both objects have the same keys and equal values, recursing into nested objects.
+    ///
+    /// Leaves are equal only if they have the same type and compare as equal. Values of different
+    /// types are never equal, even `1i32` and `1i64`. Floats follow IEEE 754 semantics: `NaN` is not
+    /// equal to anything, including itself, and `0.0` equals `-0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let a = object!({
+    ///     foo: "bar",
+    ///     baz: {
+    ///         qux: 1,
+    ///     },
+    /// });
+    /// let b = object!({
+    ///     foo: "bar",
+    ///     baz: {
+    ///         qux: 1,
+    ///     },
+    /// });
+    /// assert!(a.deep_eq(&b));
+    /// assert!(!a.deep_eq(&object!({ foo: "bar", })));
+    /// ```
+    pub fn deep_eq(&self, other: &Object) -> bool {
+        self.map.len() == other.map.len()
+            && self.map.iter().all(|(key, value)| {
+                other
+                    .map
+                    .get(key)
+                    .is_some_and(|other| values_deep_eq(&**value, &**other))
+            })
+    }
+
+    /// Recursively removes nested objects that are empty.
+    ///
+    /// Nested objects are pruned bottom-up, so an object whose only children are empty objects is
+    /// removed as well. Only `Object` values are ever removed; other leaves, including empty strings
+    /// or collections, are kept. The `Object` itself is never removed, even if it ends up empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut config = object!({
+    ///     name: "app",
+    ///     db: {
+    ///         pool: {},
+    ///     },
+    /// });
+    /// config.prune_empty();
+    /// assert!(config.get("db").is_none());
+    /// assert_eq!(config.get_as::<&str>("name"), Some(&"app"));
+    /// ```
+    pub fn prune_empty(&mut self) {
+        self.map
+            .retain(|_, value| match (**value).downcast_mut::<Object>() {
+                Some(object) => {
+                    object.prune_empty();
+                    !object.map.is_empty()
+                }
+                None => true,
+            });
+    }
+
+    /// Like [`Object::deep_eq`], but skips the keys listed in `ignore`.
+    ///
+    /// Entries of `ignore` are dotted paths, so `"meta.updated_at"` ignores the `updated_at` key
+    /// of the nested `meta` object. An ignored key may be present in either object, both or neither.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let a = object!({
+    ///     id: 1,
+    ///     name: "foo",
+    ///     meta: {
+    ///         updated_at: 100,
+    ///     },
+    /// });
+    /// let b = object!({
+    ///     id: 2,
+    ///     name: "foo",
+    ///     meta: {
+    ///         updated_at: 200,
+    ///     },
+    /// });
+    /// assert!(a.eq_ignoring(&b, &["id", "meta.updated_at"]));
+    /// assert!(!a.eq_ignoring(&b, &["id"]));
+    /// ```
+    pub fn eq_ignoring(&self, other: &Object, ignore: &[&str]) -> bool {
+        self.eq_ignoring_at(other, ignore, "")
+    }
+
+    fn eq_ignoring_at(&self, other: &Object, ignore: &[&str], prefix: &str) -> bool {
+        let path = |key: &str| {
+            if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{prefix}.{key}")
+            }
+        };
+        let matches = self.map.iter().all(|(key, value)| {
+            let path = path(key);
+            if ignore.contains(&path.as_str()) {
+                return true;
+            }
+            let Some(other) = other.map.get(key) else {
+                return false;
+            };
+            match (
+                (**value).downcast_ref::<Object>(),
+                (**other).downcast_ref::<Object>(),
+            ) {
+                (Some(value), Some(other)) => value.eq_ignoring_at(other, ignore, &path),
+                _ => **value == **other,
+            }
+        });
+        matches
+            && other
+                .map
+                .keys()
+                .filter(|key| !self.map.contains_key(*key))
+                .all(|key| ignore.contains(&path(key).as_str()))
+    }
+
+    /// Removes every entry whose value is of type `T`, yielding the keys and owned values.
+    ///
+    /// Entries of other types are kept. The entries are removed as soon as this method is called,
+    /// whether or not the returned iterator is consumed, and are yielded in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut obj = object!({
+    ///     a: 1,
+    ///     b: "two",
+    ///     c: 3,
+    /// });
+    /// let ints: Vec<_> = obj.drain_as::<i32>().collect();
+    /// assert_eq!(ints, vec![("a".to_string(), 1), ("c".to_string(), 3)]);
+    /// assert_eq!(obj.len(), 1);
+    /// ```
+    pub fn drain_as<T: 'static>(&mut self) -> impl Iterator<Item = (String, T)> {
+        let (drained, kept): (BTreeMap<_, _>, _) = std::mem::take(&mut self.map)
+            .into_iter()
+            .partition(|(_, value)| (**value).as_any().is::<T>());
+        self.map = kept;
+        drained.into_iter().filter_map(|(key, value)| {
+            let value = AnyType::into_any(value).downcast::<T>().ok()?;
+            Some((key, *value))
+        })
+    }
+
+    /// Returns a mutable reference to the value at the dotted `path`, inserting `default()` if it is absent.
+    ///
+    /// Missing intermediate segments are created as empty objects. If an intermediate segment holds
+    /// a value that is not an `Object`, `PathError::NotAnObject` is returned, and if the leaf holds a
+    /// value that is not of type `T`, `PathError::WrongType` is returned. If a missing segment
+    /// would have to be added to a [sealed](Object::seal) `Object`, `PathError::Sealed` is
+    /// returned. In all cases the `Object` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut stats = Object::new();
+    /// *stats.path_entry_as("requests.count", || 0)? += 1;
+    /// *stats.path_entry_as("requests.count", || 0)? += 1;
+    /// let requests = stats.get_as::<Object>("requests").unwrap();
+    /// assert_eq!(requests.get_as::<i32>("count"), Some(&2));
+    /// # Ok::<(), dynamic_object::PathError>(())
+    /// ```
+    pub fn path_entry_as<T: AnyType>(
+        &mut self,
+        path: &str,
+        default: impl FnOnce() -> T,
+    ) -> Result<&mut T, PathError> {
+        let (parents, leaf) = match path.rsplit_once('.') {
+            Some((parents, leaf)) => (Some(parents), leaf),
+            None => (None, path),
+        };
+        let mut object = self;
+        let mut end = 0;
+        for segment in parents.into_iter().flat_map(|parents| parents.split('.')) {
+            end += segment.len();
+            if object.sealed && !object.map.contains_key(segment) {
+                return Err(PathError::Sealed {
+                    path: path[..end].to_string(),
+                });
+            }
+            let value = object
+                .map
+                .entry(segment.to_string())
+                .or_insert_with(|| Box::new(Object::new()));
+            object = (**value)
+                .downcast_mut::<Object>()
+                .ok_or_else(|| PathError::NotAnObject {
+                    path: path[..end].to_string(),
+                })?;
+            end += 1;
+        }
+        if object.sealed && !object.map.contains_key(leaf) {
+            return Err(PathError::Sealed {
+                path: path.to_string(),
+            });
+        }
+        let value = object
+            .map
+            .entry(leaf.to_string())
+            .or_insert_with(|| Box::new(default()));
+        let found = (**value).dyn_type_name();
+        (**value)
+            .downcast_mut::<T>()
+            .ok_or_else(|| PathError::WrongType {
+                path: path.to_string(),
+                expected: any::type_name::<T>(),
+                found,
+            })
+    }
+
+    /// Replaces every value of type `T` with `f(&value)`, leaving values of other types untouched.
+    ///
+    /// Nested objects are not visited, unless `T` is `Object` itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut obj = object!({
+    ///     a: "1".to_string(),
+    ///     b: "2".to_string(),
+    ///     c: true,
+    /// });
+    /// obj.map_values_as(|s: &String| s.parse::<i32>().unwrap_or_default());
+    /// assert_eq!(obj.get_as::<i32>("a"), Some(&1));
+    /// assert_eq!(obj.get_as::<i32>("b"), Some(&2));
+    /// assert_eq!(obj.get_as::<bool>("c"), Some(&true));
+    /// ```
+    pub fn map_values_as<T: 'static, U: AnyType>(&mut self, mut f: impl FnMut(&T) -> U) {
+        for value in self.map.values_mut() {
+            if let Some(old) = (**value).downcast_ref::<T>() {
+                *value = Box::new(f(old));
+            }
+        }
+    }
+
+    /// Upcasts the `Object` to `Any`.
+    ///
+    /// This mirrors [`AnyType::as_any`] for generic code that passes objects around as `&dyn Any`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let object = Object::new();
+    /// let any = object.as_any();
+    /// assert!(any.downcast_ref::<Object>().is_some());
+    /// ```
+    pub fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Upcasts the `Object` to `Any` mutably.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// if let Some(object) = object.as_any_mut().downcast_mut::<Object>() {
+    ///     object.insert("key", "value");
+    /// }
+    /// assert_eq!(object.get_as::<&str>("key"), Some(&"value"));
+    /// ```
+    pub fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    /// Keeps only the values whose dotted path matches one of the `allowed` patterns.
+    ///
+    /// A pattern matches a path exactly, or ends with `.*` to match everything beneath a prefix, so
+    /// `db.*` keeps `db.host` and `db.pool.size`, and `*` alone matches everything. A pattern naming
+    /// a nested object keeps it whole.
+    /// Nested objects left empty by the filtering are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut config = object!({
+    ///     name: "app",
+    ///     secret: "hunter2",
+    ///     db: {
+    ///         host: "localhost",
+    ///         password: "hunter2",
+    ///     },
+    ///     cache: {
+    ///         size: 10,
+    ///     },
+    /// });
+    /// config.retain_paths(&["name", "db.host", "cache.*"]);
+    /// let keys: Vec<_> = config.keys().cloned().collect();
+    /// assert_eq!(keys, vec!["cache", "db", "name"]);
+    /// let db = config.get_as::<dynamic_object::Object>("db").unwrap();
+    /// assert!(db.get("password").is_none());
+    /// ```
+    pub fn retain_paths(&mut self, allowed: &[&str]) {
+        self.retain_paths_at(allowed, "");
+    }
+
+    fn retain_paths_at(&mut self, allowed: &[&str], prefix: &str) {
+        self.map.retain(|key, value| {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            let matches = allowed
+                .iter()
+                .any(|pattern| match pattern.strip_suffix(".*") {
+                    Some(parent) => path
+                        .strip_prefix(parent)
+                        .is_some_and(|rest| rest.starts_with('.')),
+                    None => *pattern == "*" || *pattern == path,
+                });
+            if matches {
+                return true;
+            }
+            match (**value).downcast_mut::<Object>() {
+                Some(object) => {
+                    object.retain_paths_at(allowed, &path);
+                    !object.map.is_empty()
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// Inserts a key-value pair, unless the key already holds a value of another type.
+    ///
+    /// Overwriting a value of the same type succeeds. If the existing value is of another type,
+    /// an [`InsertError::TypeConflict`] naming both types is returned and the existing value is
+    /// kept. A new key is rejected with [`InsertError::Sealed`] if the `Object` is
+    /// [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// assert!(object.insert_checked("port", 80).is_ok());
+    /// assert!(object.insert_checked("port", 8080).is_ok());
+    /// let error = object.insert_checked("port", "8080").unwrap_err();
+    /// assert_eq!(error.to_string(), "key `port` holds a value of type `i32`, expected `&str`");
+    /// assert_eq!(object.get_as::<i32>("port"), Some(&8080));
+    /// ```
+    pub fn insert_checked<V: AnyType>(
+        &mut self,
+        key: impl Into<String>,
+        value: V,
+    ) -> Result<(), InsertError> {
+        match self.map.entry(key.into()) {
+            Entry::Vacant(entry) if self.sealed => Err(InsertError::Sealed {
+                key: entry.into_key(),
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(Box::new(value));
+                Ok(())
+            }
+            Entry::Occupied(mut entry) => match (**entry.get_mut()).downcast_mut::<V>() {
+                Some(existing) => {
+                    *existing = value;
+                    Ok(())
+                }
+                None => Err(InsertError::TypeConflict(TypeConflict {
+                    key: entry.key().clone(),
+                    expected: any::type_name::<V>(),
+                    found: (**entry.get()).dyn_type_name(),
+                })),
+            },
+        }
+    }
+
+    /// Returns an iterator over the nested objects, in key order.
+    ///
+    /// Values that are not an `Object` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({
+    ///     a: {
+    ///         inner: 1,
+    ///     },
+    ///     b: 2,
+    /// });
+    /// let keys: Vec<_> = obj.iter_objects().map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(keys, vec!["a"]);
+    /// ```
+    pub fn iter_objects(&self) -> impl Iterator<Item = (&String, &Object)> {
+        self.map
+            .iter()
+            .filter_map(|(key, value)| Some((key, (**value).downcast_ref::<Object>()?)))
+    }
+
+    /// Returns an iterator over mutable references to the nested objects, in key order.
+    ///
+    /// Values that are not an `Object` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut obj = object!({
+    ///     a: {},
+    ///     b: {},
+    ///     c: 3,
+    /// });
+    /// for (key, object) in obj.iter_objects_mut() {
+    ///     object.insert("name", key.clone());
+    /// }
+    /// let a = obj.get_as::<dynamic_object::Object>("a").unwrap();
+    /// assert_eq!(a.get_as::<String>("name"), Some(&"a".to_string()));
+    /// ```
+    pub fn iter_objects_mut(&mut self) -> impl Iterator<Item = (&String, &mut Object)> {
+        self.map
+            .iter_mut()
+            .filter_map(|(key, value)| Some((key, (**value).downcast_mut::<Object>()?)))
+    }
+
+    /// Inserts the inner value of `value` if it is `Some`, or removes the key if it is `None`.
+    ///
+    /// The value is stored as a plain `T`, never as an `Option<T>`, so it can be read back with
+    /// `get_as::<T>`. Passing `None` removes any value previously stored under the key, so that an
+    /// absent key always means "no value".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert_optional("a", Some(1));
+    /// object.insert_optional("b", None::<i32>);
+    /// assert_eq!(object.get_as::<i32>("a"), Some(&1));
+    /// assert!(!object.contains_key("b"));
+    /// object.insert_optional("a", None::<i32>);
+    /// assert!(!object.contains_key("a"));
+    /// ```
+    pub fn insert_optional<T: AnyType>(&mut self, key: impl Into<String>, value: Option<T>) {
+        let key = key.into();
+        match value {
+            Some(value) => self.insert(key, value),
+            None => {
+                self.map.remove(&key);
+            }
+        }
+    }
+
+    /// Inserts a key-value pair, converting borrowed strings into an owned `String` first.
+    ///
+    /// String literals are `&'static str`, so with [`Object::insert`] they must be read back with
+    /// `get_as::<&str>` rather than `get_as::<String>`. This method stores `&str`, `Cow<str>` and
+    /// `Box<str>` values as `String` instead; any other value is inserted unchanged. Only the value
+    /// itself is converted, so nested objects or arrays holding `&str` are left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert_owned("name", "app");
+    /// object.insert_owned("path", Cow::Borrowed("/tmp"));
+    /// object.insert_owned("port", 80);
+    /// assert_eq!(object.get_as::<String>("name").unwrap(), "app");
+    /// assert_eq!(object.get_as::<String>("path").unwrap(), "/tmp");
+    /// assert!(object.get_as::<&str>("name").is_none());
+    /// assert_eq!(object.get_as::<i32>("port"), Some(&80));
+    /// ```
+    pub fn insert_owned<K: Into<String>, V: AnyType>(&mut self, key: K, value: V) {
+        let value: Box<dyn Any> = Box::new(value);
+        let value = match value.downcast::<&str>() {
+            Ok(s) => return self.insert(key, s.to_string()),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<Cow<'static, str>>() {
+            Ok(s) => return self.insert(key, s.into_owned()),
+            Err(value) => value,
+        };
+        let value = match value.downcast::<Box<str>>() {
+            Ok(s) => return self.insert(key, String::from(*s)),
+            Err(value) => value,
+        };
+        let value = value.downcast::<V>().expect("the value is of type `V`");
+        self.insert(key, *value);
+    }
+
+    /// Returns a reference to the value corresponding to the key, looking through a stored `Option<T>`.
+    ///
+    /// Returns `Some` if the key holds a `T` or a `Some(T)`, and `None` if the key is absent, holds
+    /// `None::<T>`, [`Null`] or a value of another type. This treats values inserted with
+    /// [`Object::insert`] and [`Object::insert_optional`] alike, as well as nulls from JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert("plain", 1);
+    /// object.insert("some", Some(2));
+    /// object.insert("none", None::<i32>);
+    /// assert_eq!(object.get_optional_as::<i32>("plain"), Some(&1));
+    /// assert_eq!(object.get_optional_as::<i32>("some"), Some(&2));
+    /// assert_eq!(object.get_optional_as::<i32>("none"), None);
+    /// assert_eq!(object.get_optional_as::<i32>("missing"), None);
+    /// ```
+    pub fn get_optional_as<T: 'static>(&self, key: &str) -> Option<&T> {
+        let value = &**self.map.get(key)?;
+        value
+            .downcast_ref::<T>()
+            .or_else(|| value.downcast_ref::<Option<T>>()?.as_ref())
+    }
+
+    /// Merges `other` into the `Object`, treating `Null` values in `other` as "unset".
+    ///
+    /// Values from `other` overwrite those in the `Object`, except `Null`, which is never inserted:
+    /// instead, the key is removed from the `Object`. Keys absent from `other` are left alone. This
+    /// gives layered configs three states per key: keep (absent), override (a value) and unset (`Null`).
+    /// Keys only in `other` are skipped if the `Object` is [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Null};
+    /// let mut config = object!({
+    ///     host: "localhost",
+    ///     port: 80,
+    ///     proxy: "proxy.local",
+    /// });
+    /// config.merge_preferring_non_null(object!({
+    ///     port: 8080,
+    ///     proxy: Null,
+    /// }));
+    /// assert_eq!(config.get_as::<&str>("host"), Some(&"localhost"));
+    /// assert_eq!(config.get_as::<i32>("port"), Some(&8080));
+    /// assert!(config.get("proxy").is_none());
+    /// ```
+    pub fn merge_preferring_non_null(&mut self, other: Object) {
+        for (key, value) in other.map {
+            if (*value).as_any().is::<Null>() {
+                self.map.remove(&key);
+            } else if let Some(existing) = self.map.get_mut(&key) {
+                *existing = value;
+            } else if !self.sealed {
+                self.map.insert(key, value);
+            }
+        }
+    }
+
+    /// Compares two objects with a total order, even if they hold values like `NaN`.
+    ///
+    /// Objects are compared entry by entry in key order, first by key, then by value, and a shorter
+    /// object that is a prefix of a longer one is less. Values are compared with
+    /// [`AnyType::dyn_total_cmp`], which breaks ties as follows:
+    ///
+    /// - Values of different types are ordered by type name, then by `TypeId`. The `TypeId` order
+    ///   is consistent within a build but not between builds.
+    /// - `f32` and `f64` values are ordered with `total_cmp`, so `-0.0 < 0.0` and a positive `NaN`
+    ///   sorts after positive infinity.
+    /// - Nested objects are compared with `cmp_total`, and arrays stored as `Vec<Box<dyn AnyType>>`
+    ///   are compared element by element with `dyn_total_cmp`.
+    /// - Any other values of the same type use `PartialOrd`. A value that isn't comparable with
+    ///   itself, like a `Vec<f64>` or an `Option<f64>` holding `NaN`, sorts after every value of
+    ///   its type that is, and such values are ordered among themselves by their `Debug`
+    ///   representation.
+    ///
+    /// The result is a total order as long as the `PartialOrd` of each stored type is total on the
+    /// values comparable with themselves, which holds for the standard numeric types and the
+    /// containers and tuples of them. Two such values that `PartialOrd` still can't compare are
+    /// ordered by their `Debug` representation too, but that order isn't transitive in general.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use dynamic_object::object;
+    /// let a = object!({
+    ///     x: f64::NAN,
+    /// });
+    /// let b = object!({
+    ///     x: 1.0,
+    /// });
+    /// assert_eq!(a.partial_cmp(&b), None);
+    /// assert_eq!(a.cmp_total(&b), Ordering::Greater);
+    /// assert_eq!(a.cmp_total(&a), Ordering::Equal);
+    /// ```
+    pub fn cmp_total(&self, other: &Object) -> Ordering {
+        self.map
+            .iter()
+            .zip(&other.map)
+            .map(|((ka, va), (kb, vb))| ka.cmp(kb).then_with(|| (**va).dyn_total_cmp(&**vb)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| self.map.len().cmp(&other.map.len()))
+    }
+
+    /// Returns the entries of the `Object` sorted by key, with the values unboxed.
+    ///
+    /// `Object` is backed by a `BTreeMap`, so this is the same order as `iter`. The method is also
+    /// reachable from `ObservableObject` and `TotalObject` through `Deref`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     b: 2,
+    ///     a: 1,
+    /// });
+    /// let keys: Vec<_> = object.sorted_entries().into_iter().map(|(k, _)| k.as_str()).collect();
+    /// assert_eq!(keys, ["a", "b"]);
+    /// ```
+    pub fn sorted_entries(&self) -> Vec<(&String, &dyn AnyType)> {
+        self.map.iter().map(|(k, v)| (k, &**v)).collect()
+    }
+
+    /// Returns the value corresponding to the key as a `&dyn Debug`, without knowing its type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     list: vec![1, 2],
+    /// });
+    /// assert_eq!(format!("{:?}", object.get_debug("list").unwrap()), "[1, 2]");
+    /// assert!(object.get_debug("missing").is_none());
+    /// ```
+    pub fn get_debug(&self, key: &str) -> Option<&dyn Debug> {
+        self.map.get(key).map(|v| v as &dyn Debug)
+    }
+
+    /// Splits the `Object` in two at `key`, returning a new `Object` with every entry whose key
+    /// is greater than or equal to `key`.
+    ///
+    /// This relies on keys being kept in lexicographic order. The values are moved, not cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut object = object!({
+    ///     a: 1,
+    ///     b: 2,
+    ///     c: 3,
+    /// });
+    /// let rest = object.split_off("b");
+    /// assert_eq!(object.keys().collect::<Vec<_>>(), ["a"]);
+    /// assert_eq!(rest.keys().collect::<Vec<_>>(), ["b", "c"]);
+    /// ```
+    pub fn split_off(&mut self, key: &str) -> Object {
+        Object::from_map(self.map.split_off(key))
+    }
+
+    /// Returns `true` if any entry holds a value equal to `value`.
+    ///
+    /// Values are compared with the `PartialEq` implementation of `dyn AnyType`, so values of
+    /// different types are never equal. This scans every entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     a: 1,
+    ///     b: "two",
+    /// });
+    /// assert!(object.contains_value(&"two"));
+    /// assert!(!object.contains_value(&1u8));
+    /// ```
+    pub fn contains_value(&self, value: &dyn AnyType) -> bool {
+        self.find_key_of(value).is_some()
+    }
+
+    /// Returns the first key, in key order, whose value is equal to `value`.
+    ///
+    /// See [`Object::contains_value`] for how values are compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     b: 1,
+    ///     a: 1,
+    /// });
+    /// assert_eq!(object.find_key_of(&1).map(String::as_str), Some("a"));
+    /// assert_eq!(object.find_key_of(&2), None);
+    /// ```
+    pub fn find_key_of(&self, value: &dyn AnyType) -> Option<&String> {
+        self.map
+            .iter()
+            .find(|(_, v)| ***v == *value)
+            .map(|(k, _)| k)
+    }
+
+    /// Returns the `TypeId` of the value corresponding to the key, or `None` if the key is absent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     key: "value",
+    /// });
+    /// assert_eq!(object.type_id_of("key"), Some(TypeId::of::<&str>()));
+    /// assert_eq!(object.type_id_of("missing"), None);
+    /// ```
+    pub fn type_id_of(&self, key: &str) -> Option<TypeId> {
+        self.map.get(key).map(|v| (**v).dyn_type_id())
+    }
+
+    /// Returns the value corresponding to the key as a `&dyn Any` if its `TypeId` is `ty`, or
+    /// `None` if the key is absent or holds a value of another type.
+    ///
+    /// This is [`Object::get_as`] for code that only learns the expected type at runtime, e.g.
+    /// from a schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     port: 8080u16,
+    /// });
+    /// let expected = TypeId::of::<u16>();
+    /// let port = object.get_by_type_id("port", expected).unwrap();
+    /// assert_eq!(port.downcast_ref::<u16>(), Some(&8080));
+    /// assert!(object.get_by_type_id("port", TypeId::of::<i32>()).is_none());
+    /// ```
+    pub fn get_by_type_id(&self, key: &str, ty: TypeId) -> Option<&dyn Any> {
+        let value = self.map.get(key)?;
+        ((**value).dyn_type_id() == ty).then(|| (**value).as_any())
+    }
+
+    /// Moves every entry whose key starts with `prefix` followed by a dot into a new `Object`,
+    /// with the prefix and the dot removed from the key.
+    ///
+    /// A trailing dot in `prefix` is optional, so `"db"` and `"db."` behave the same. Keys equal
+    /// to the prefix, or merely starting with it like `"dbx.host"`, are left in place. The values
+    /// are moved, not cloned: since arbitrary values can't be cloned, there is no borrowing variant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut config = Object::new();
+    /// config.insert("db.host", "localhost");
+    /// config.insert("db.port", 5432);
+    /// config.insert("name", "app");
+    /// let db = config.strip_prefix("db.");
+    /// assert_eq!(db.get_as::<&str>("host"), Some(&"localhost"));
+    /// assert_eq!(db.get_as::<i32>("port"), Some(&5432));
+    /// assert_eq!(config.keys().collect::<Vec<_>>(), ["name"]);
+    /// ```
+    pub fn strip_prefix(&mut self, prefix: &str) -> Object {
+        let prefix = format!("{}.", prefix.strip_suffix('.').unwrap_or(prefix));
+        let keys: Vec<String> = self
+            .map
+            .range(prefix.clone()..)
+            .map(|(k, _)| k)
+            .take_while(|k| k.starts_with(&prefix))
+            .filter(|k| k.len() > prefix.len())
+            .cloned()
+            .collect();
+        let mut stripped = Object::new();
+        for key in keys {
+            let value = self.map.remove(&key).expect("the key was just found");
+            stripped.map.insert(key[prefix.len()..].to_string(), value);
+        }
+        stripped
+    }
+
+    /// Prefixes every top-level key with `prefix` followed by a dot, the inverse of
+    /// [`Object::strip_prefix`].
+    ///
+    /// As with `strip_prefix`, a trailing dot in `prefix` is optional, and an empty prefix (or a
+    /// lone `"."`) leaves the keys unchanged. Only the top level is renamed: the keys inside
+    /// nested objects are left untouched, since they are already namespaced by their parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let mut db = object!({
+    ///     host: "localhost",
+    ///     pool: {
+    ///         size: 4,
+    ///     },
+    /// });
+    /// db.prefix_all_keys("db");
+    /// assert_eq!(db.keys().collect::<Vec<_>>(), ["db.host", "db.pool"]);
+    /// let pool = db.get_as::<Object>("db.pool").unwrap();
+    /// assert_eq!(pool.keys().collect::<Vec<_>>(), ["size"]);
+    ///
+    /// assert_eq!(db.strip_prefix("db"), object!({ host: "localhost", pool: { size: 4, }, }));
+    /// ```
+    pub fn prefix_all_keys(&mut self, prefix: &str) {
+        let prefix = prefix.strip_suffix('.').unwrap_or(prefix);
+        if prefix.is_empty() {
+            return;
+        }
+        self.map = std::mem::take(&mut self.map)
+            .into_iter()
+            .map(|(key, value)| (format!("{prefix}.{key}"), value))
+            .collect();
+    }
+
+    /// Recursively removes the leaves for which `f` returns `false`, then removes the nested
+    /// objects left empty.
+    ///
+    /// Leaves are all values that are not an `Object`. `f` receives the dotted path of each leaf,
+    /// such as `"db.port"`, and its value. Nested objects that are empty after filtering are
+    /// removed too, including those that were empty to begin with, but the `Object` itself never is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let mut config = object!({
+    ///     name: "app",
+    ///     db: {
+    ///         password: "secret",
+    ///     },
+    ///     api: {
+    ///         password: "secret",
+    ///         url: "https://example.com",
+    ///     },
+    /// });
+    /// config.retain_leaves(|path, _| !path.ends_with("password"));
+    /// assert!(config.get("db").is_none());
+    /// assert_eq!(config.get_as::<Object>("api").unwrap().len(), 1);
+    /// ```
+    pub fn retain_leaves(&mut self, mut f: impl FnMut(&str, &dyn AnyType) -> bool) {
+        self.retain_leaves_at(&mut f, "");
+    }
+
+    fn retain_leaves_at(&mut self, f: &mut impl FnMut(&str, &dyn AnyType) -> bool, prefix: &str) {
+        self.map.retain(|key, value| {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match (**value).downcast_mut::<Object>() {
+                Some(object) => {
+                    object.retain_leaves_at(f, &path);
+                    !object.map.is_empty()
+                }
+                None => f(&path, &**value),
+            }
+        });
+    }
+
+    /// Returns the number of leaves in the `Object`, counting recursively.
+    ///
+    /// Nested objects and arrays stored as `Vec<Box<dyn AnyType>>` are descended into rather than
+    /// counted; every other value is one leaf. Together with [`Object::max_depth`], this is a
+    /// cheap way to reject pathologically large inputs before processing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, AnyType};
+    /// let object = object!({
+    ///     a: 1,
+    ///     b: {
+    ///         c: 2,
+    ///         d: {},
+    ///     },
+    ///     e: vec![Box::new(3) as Box<dyn AnyType>, Box::new(4)],
+    /// });
+    /// assert_eq!(object.count_leaves(), 4);
+    /// ```
+    pub fn count_leaves(&self) -> usize {
+        self.map.values().map(|v| count_leaves(&**v)).sum()
+    }
+
+    /// Returns the maximum nesting depth of the `Object`.
+    ///
+    /// The `Object` itself is at depth 1, and every nested object or array stored as
+    /// `Vec<Box<dyn AnyType>>` adds one level, whether or not it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, AnyType, Object};
+    /// assert_eq!(Object::new().max_depth(), 1);
+    /// let object = object!({
+    ///     a: 1,
+    ///     b: {
+    ///         c: vec![Box::new(object!({})) as Box<dyn AnyType>],
+    ///     },
+    /// });
+    /// assert_eq!(object.max_depth(), 4);
+    /// ```
+    pub fn max_depth(&self) -> usize {
+        1 + self.map.values().map(|v| depth(&**v)).max().unwrap_or(0)
+    }
+
+    /// Replaces the characters of `key` that aren't ASCII alphanumeric with `_`.
+    ///
+    /// This is the default sanitizer for [`Object::sanitize_keys`] and
+    /// [`Object::try_sanitize_keys`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// assert_eq!(Object::underscore_non_alphanumeric("db.host-name"), "db_host_name");
+    /// ```
+    pub fn underscore_non_alphanumeric(key: &str) -> String {
+        key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect()
+    }
+
+    /// Replaces every key with the result of `f`, recursing into nested objects.
+    ///
+    /// If several keys of the same object map to the same new key, the last of them in key order
+    /// wins and the others are dropped. Use [`Object::try_sanitize_keys`] to detect that instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut env = Object::new();
+    /// env.insert("log-level", "debug");
+    /// env.sanitize_keys(|key| Object::underscore_non_alphanumeric(key).to_uppercase());
+    /// assert_eq!(env.get_as::<&str>("LOG_LEVEL"), Some(&"debug"));
+    /// ```
+    pub fn sanitize_keys(&mut self, f: impl Fn(&str) -> String) {
+        self.sanitize_keys_with(&f);
+    }
+
+    fn sanitize_keys_with(&mut self, f: &impl Fn(&str) -> String) {
+        for (key, mut value) in std::mem::take(&mut self.map) {
+            if let Some(object) = (*value).downcast_mut::<Object>() {
+                object.sanitize_keys_with(f);
+            }
+            self.map.insert(f(&key), value);
+        }
+    }
+
+    /// Like [`Object::sanitize_keys`], but fails if two keys of the same object would map to the
+    /// same new key.
+    ///
+    /// The error holds the dotted path of the colliding key, made of sanitized keys, and the
+    /// `Object` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// object.insert("a-b", 1);
+    /// object.insert("a.b", 2);
+    /// let collision = object.try_sanitize_keys(Object::underscore_non_alphanumeric).unwrap_err();
+    /// assert_eq!(collision.key, "a_b");
+    /// assert!(object.contains_key("a-b"));
+    /// ```
+    pub fn try_sanitize_keys(&mut self, f: impl Fn(&str) -> String) -> Result<(), KeyCollision> {
+        if let Some(key) = self.find_sanitize_collision(&f, "") {
+            return Err(KeyCollision { key });
+        }
+        self.sanitize_keys_with(&f);
+        Ok(())
+    }
+
+    fn find_sanitize_collision(&self, f: &impl Fn(&str) -> String, prefix: &str) -> Option<String> {
+        let mut seen = BTreeSet::new();
+        for (key, value) in &self.map {
+            let key = f(key);
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            if let Some(object) = (**value).downcast_ref::<Object>() {
+                if let Some(collision) = object.find_sanitize_collision(f, &path) {
+                    return Some(collision);
+                }
+            }
+            if !seen.insert(key) {
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    /// Returns an iterator over the entries with mutable access to the boxed values, in key order.
+    ///
+    /// Unlike typed accessors, this allows replacing a value with one of a different type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut object = object!({
+    ///     a: 1,
+    ///     b: "two",
+    /// });
+    /// for (_, value) in object.values_boxed_mut() {
+    ///     if let Some(n) = value.downcast_ref::<i32>() {
+    ///         *value = Box::new(n.to_string());
+    ///     }
+    /// }
+    /// assert_eq!(object.get_as::<String>("a").unwrap(), "1");
+    /// ```
+    pub fn values_boxed_mut(&mut self) -> impl Iterator<Item = (&String, &mut Box<dyn AnyType>)> {
+        self.map.iter_mut()
+    }
+
+    /// Removes the entries whose value is equal to the value of an earlier entry, in key order,
+    /// and returns their keys.
+    ///
+    /// Values are compared as in [`Object::contains_value`], so `NaN` is never a duplicate. Every
+    /// value is compared with every kept value, so this takes quadratic time and is meant for
+    /// small objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut object = object!({
+    ///     a: 1,
+    ///     b: 2,
+    ///     c: 1,
+    ///     d: 1u8,
+    /// });
+    /// assert_eq!(object.dedup_values(), ["c"]);
+    /// assert_eq!(object.keys().collect::<Vec<_>>(), ["a", "b", "d"]);
+    /// ```
+    pub fn dedup_values(&mut self) -> Vec<String> {
+        let mut kept: Vec<&dyn AnyType> = Vec::new();
+        let mut removed = Vec::new();
+        for (key, value) in &self.map {
+            if kept.iter().any(|k| **k == **value) {
+                removed.push(key.clone());
+            } else {
+                kept.push(&**value);
+            }
+        }
+        for key in &removed {
+            self.map.remove(key);
+        }
+        removed
+    }
+
+    /// Removes the value at a dotted path, returning whether it existed.
+    ///
+    /// Every segment but the last must name a nested `Object`. If one is missing or holds another
+    /// type, nothing is removed and `false` is returned. Intermediate objects are kept even if
+    /// they end up empty; see [`Object::remove_path_and_prune`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let mut config = object!({
+    ///     db: {
+    ///         password: "secret",
+    ///     },
+    /// });
+    /// assert!(config.remove_path("db.password"));
+    /// assert!(!config.remove_path("db.password"));
+    /// assert!(config.get_as::<Object>("db").unwrap().is_empty());
+    /// ```
+    pub fn remove_path(&mut self, path: &str) -> bool {
+        self.remove_path_at(path, false)
+    }
+
+    /// Like [`Object::remove_path`], but also removes the intermediate objects that end up empty.
+    ///
+    /// Only objects on the path are pruned, and the `Object` itself is never removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut config = object!({
+    ///     db: {
+    ///         auth: {
+    ///             password: "secret",
+    ///         },
+    ///     },
+    ///     empty: {},
+    /// });
+    /// assert!(config.remove_path_and_prune("db.auth.password"));
+    /// assert!(config.get("db").is_none());
+    /// assert!(config.get("empty").is_some());
+    /// ```
+    pub fn remove_path_and_prune(&mut self, path: &str) -> bool {
+        self.remove_path_at(path, true)
+    }
+
+    fn remove_path_at(&mut self, path: &str, prune: bool) -> bool {
+        let Some((segment, rest)) = path.split_once('.') else {
+            return self.map.remove(path).is_some();
+        };
+        let Some(object) = self
+            .map
+            .get_mut(segment)
+            .and_then(|v| (**v).downcast_mut::<Object>())
+        else {
+            return false;
+        };
+        let removed = object.remove_path_at(rest, prune);
+        if removed && prune && object.map.is_empty() {
+            self.map.remove(segment);
+        }
+        removed
+    }
+
+    /// Consumes the `Object`, returning its entries in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     b: 2,
+    ///     a: 1,
+    /// });
+    /// let pairs = object.into_pairs();
+    /// assert_eq!(pairs[0].0, "a");
+    /// assert_eq!(pairs[1].1.downcast_ref::<i32>(), Some(&2));
+    /// ```
+    pub fn into_pairs(self) -> Vec<(String, Box<dyn AnyType>)> {
+        self.map.into_iter().collect()
+    }
+
+    /// Consumes the `Object` and returns the value corresponding to the key if it is of type `T`,
+    /// or `None` if it is absent or of another type.
+    ///
+    /// The value is moved out rather than cloned, and the rest of the `Object` is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     name: "app".to_string(),
+    ///     port: 80,
+    /// });
+    /// let name: String = object.into_get_as("name").unwrap();
+    /// assert_eq!(name, "app");
+    /// ```
+    pub fn into_get_as<T: 'static>(mut self, key: &str) -> Option<T> {
+        let value = AnyType::into_any(self.map.remove(key)?);
+        value.downcast::<T>().ok().map(|value| *value)
+    }
+
+    /// Returns a reference to the value corresponding to the key if it is of type `T`, or an
+    /// error mentioning `ctx` if it is absent or of another type.
+    ///
+    /// `ctx` describes the caller, e.g. the config section being read, so that errors from a
+    /// shared helper can be told apart.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let db = object!({
+    ///     port: "5432",
+    /// });
+    /// let error = db.get_as_ctx::<i32>("port", "database config").unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "database config: key `port` holds a value of type `&str`, expected `i32`"
+    /// );
+    /// ```
+    pub fn get_as_ctx<T: 'static>(&self, key: &str, ctx: &str) -> Result<&T, AccessError> {
+        let value = self.map.get(key).ok_or_else(|| AccessError::Missing {
+            key: key.to_string(),
+            context: ctx.to_string(),
+        })?;
+        (**value)
+            .downcast_ref::<T>()
+            .ok_or_else(|| AccessError::WrongType {
+                key: key.to_string(),
+                expected: any::type_name::<T>(),
+                found: (**value).dyn_type_name(),
+                context: ctx.to_string(),
+            })
+    }
+
+    /// Returns `true` if the key holds [`Null`] or `()`.
+    ///
+    /// Absent keys are not null.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Null};
+    /// let object = object!({
+    ///     unset: Null,
+    ///     set: 1,
+    /// });
+    /// assert!(object.is_null("unset"));
+    /// assert!(!object.is_null("set"));
+    /// assert!(!object.is_null("missing"));
+    /// ```
+    pub fn is_null(&self, key: &str) -> bool {
+        self.map.get(key).is_some_and(|v| {
+            let value = (**v).as_any();
+            value.is::<Null>() || value.is::<()>()
+        })
+    }
+
+    /// Returns a reference to the value corresponding to the key if it is of type `T`, or inserts
+    /// the result of `f` if the key is absent.
+    ///
+    /// `f` is only called when the key is absent, and its error is returned as-is, leaving the
+    /// `Object` unchanged. If the key holds a value of another type, or is absent and the
+    /// `Object` is [sealed](Object::seal), `Ok(None)` is returned without calling `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// let port = object.get_or_try_insert_with_as("port", || "8080".parse::<u16>());
+    /// assert_eq!(port, Ok(Some(&mut 8080)));
+    /// let error = object.get_or_try_insert_with_as("other", || "x".parse::<u16>());
+    /// assert!(error.is_err());
+    /// assert!(!object.contains_key("other"));
+    /// ```
+    pub fn get_or_try_insert_with_as<T: AnyType, E>(
+        &mut self,
+        key: impl Into<String>,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<Option<&mut T>, E> {
+        let bx = match self.map.entry(key.into()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(_) if self.sealed => return Ok(None),
+            Entry::Vacant(entry) => entry.insert(Box::new(f()?)),
+        };
+        Ok((**bx).as_any_mut().downcast_mut::<T>())
+    }
+
+    /// Keeps the `n` entries of type `T` with the largest values, removing the other entries of
+    /// type `T`. Entries of other types are left untouched.
+    ///
+    /// Values are compared with `PartialOrd`. Values that aren't comparable with themselves, like
+    /// a float `NaN`, rank above all others, and the remaining values are expected to be totally
+    /// ordered; two of them that still can't be compared count as a tie. Ties are broken by key
+    /// order, keeping the smaller keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut scores = object!({
+    ///     alice: 30,
+    ///     bob: 10,
+    ///     carol: 30,
+    ///     dave: 20,
+    ///     name: "leaderboard",
+    /// });
+    /// scores.retain_top_n::<i32>(2);
+    /// assert_eq!(
+    ///     scores,
+    ///     object!({
+    ///         alice: 30,
+    ///         carol: 30,
+    ///         name: "leaderboard",
+    ///     })
+    /// );
+    /// ```
+    pub fn retain_top_n<T: 'static + PartialOrd>(&mut self, n: usize) {
+        let (mut ranked, unordered): (Vec<_>, Vec<_>) = self
+            .map
+            .iter()
+            .filter_map(|(key, value)| Some((key, (**value).downcast_ref::<T>()?)))
+            .partition(|(_, value)| value.partial_cmp(value).is_some());
+        ranked.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+        let evicted: Vec<String> = unordered
+            .into_iter()
+            .chain(ranked)
+            .skip(n)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in evicted {
+            self.map.remove(&key);
+        }
+    }
+
+    /// Returns the values corresponding to two keys, as types `A` and `B` respectively.
+    ///
+    /// Each half of the pair is `None` if its key is absent or holds a value of another type, just
+    /// like [`Object::get_as`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     host: "localhost",
+    ///     port: 8080,
+    /// });
+    /// let (host, port) = object.get_two_as::<&str, i32>("host", "port");
+    /// assert_eq!((host, port), (Some(&"localhost"), Some(&8080)));
+    /// assert_eq!(object.get_two_as::<i32, i32>("host", "port"), (None, Some(&8080)));
+    /// ```
+    pub fn get_two_as<A: 'static, B: 'static>(
+        &self,
+        ka: &str,
+        kb: &str,
+    ) -> (Option<&A>, Option<&B>) {
+        (self.get_as::<A>(ka), self.get_as::<B>(kb))
+    }
+
+    /// Folds the values of type `T` into an accumulator, in key order.
+    ///
+    /// `f` receives the accumulator, the key and the value, and returns the new accumulator.
+    /// Values of other types are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     a: 1,
+    ///     b: 2,
+    ///     c: "three",
+    ///     d: 4,
+    /// });
+    /// assert_eq!(object.fold_as::<i32, _>(0, |sum, _, n| sum + n), 7);
+    /// let keys = object.fold_as::<i32, _>(String::new(), |mut keys, key, _| {
+    ///     keys.push_str(key);
+    ///     keys
+    /// });
+    /// assert_eq!(keys, "abd");
+    /// ```
+    pub fn fold_as<T: 'static, Acc>(
+        &self,
+        init: Acc,
+        mut f: impl FnMut(Acc, &str, &T) -> Acc,
+    ) -> Acc {
+        self.map.iter().fold(init, |acc, (key, value)| {
+            match (**value).downcast_ref::<T>() {
+                Some(value) => f(acc, key, value),
+                None => acc,
+            }
+        })
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key, inserting `default` if
+    /// it is absent or replacing the existing value with `default` if it isn't of type `T`.
+    ///
+    /// Unlike [`Object::get_or_insert_as`], this always returns a `&mut T`, at the cost of
+    /// silently discarding a value of another type. Only use it for keys whose type is owned by
+    /// the calling code. For the same reason, it adds the key even if the `Object` is
+    /// [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut stats = object!({
+    ///     hits: "not a number",
+    /// });
+    /// *stats.mut_or_replace_as("hits", 0) += 1;
+    /// *stats.mut_or_replace_as("hits", 0) += 1;
+    /// assert_eq!(stats.get_as::<i32>("hits"), Some(&2));
+    /// ```
+    pub fn mut_or_replace_as<T: AnyType>(&mut self, key: impl Into<String>, default: T) -> &mut T {
+        let bx = match self.map.entry(key.into()) {
+            Entry::Occupied(entry) => {
+                let bx = entry.into_mut();
+                if !(**bx).as_any().is::<T>() {
+                    *bx = Box::new(default);
+                }
+                bx
+            }
+            Entry::Vacant(entry) => entry.insert(Box::new(default)),
+        };
+        (**bx)
+            .as_any_mut()
+            .downcast_mut::<T>()
+            .expect("the value is of type `T`")
+    }
+
+    /// Returns `true` if both objects have exactly the same keys, whatever their values.
+    ///
+    /// Only the top level is compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let a = object!({ x: 1, y: "two", });
+    /// assert!(a.same_keys(&object!({ x: 'x', y: { z: 3, }, })));
+    /// assert!(!a.same_keys(&object!({ x: 1, })));
+    /// ```
+    pub fn same_keys(&self, other: &Object) -> bool {
+        self.map.len() == other.map.len() && self.map.keys().eq(other.map.keys())
+    }
+
+    /// Compares the top-level keys of the two objects, returning the keys only `other` has
+    /// (added) and the keys only `self` has (removed), both in key order.
+    ///
+    /// Values are never compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let old = object!({ host: "a", port: 80, });
+    /// let new = object!({ host: "b", tls: true, });
+    /// let (added, removed) = old.key_set_diff(&new);
+    /// assert_eq!(added, ["tls"]);
+    /// assert_eq!(removed, ["port"]);
+    /// ```
+    pub fn key_set_diff<'a>(&'a self, other: &'a Object) -> (Vec<&'a str>, Vec<&'a str>) {
+        let added = other
+            .map
+            .keys()
+            .filter(|key| !self.map.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        let removed = self
+            .map
+            .keys()
+            .filter(|key| !other.map.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        (added, removed)
+    }
+
+    /// Returns a mutable reference to the nested `Object` under the key, inserting an empty one if
+    /// the key is absent.
+    ///
+    /// An existing `Object` is returned as-is, while a value of any other type is overwritten, as
+    /// with [`Object::mut_or_replace_as`]. Like it, this adds the key even if the `Object` is
+    /// [sealed](Object::seal).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let mut config = Object::new();
+    /// let db = config.insert_object("db");
+    /// db.insert("host", "localhost");
+    /// db.insert_object("pool").insert("size", 4);
+    /// config.insert_object("db").insert("port", 5432);
+    /// assert_eq!(
+    ///     config,
+    ///     object!({
+    ///         db: {
+    ///             host: "localhost",
+    ///             pool: {
+    ///                 size: 4,
+    ///             },
+    ///             port: 5432,
+    ///         },
+    ///     })
+    /// );
+    /// ```
+    pub fn insert_object(&mut self, key: impl Into<String>) -> &mut Object {
+        self.mut_or_replace_as(key, Object::new())
+    }
+
+    /// Follows `keys` through nested objects and returns the value at the end, whatever its
+    /// type.
+    ///
+    /// Every key but the last must lead to an `Object`. Returns `None` if a key is absent or
+    /// leads to a value of another type before the end. An empty `keys` returns the `Object`
+    /// itself. Unlike dotted paths, keys may contain dots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     db: {
+    ///         primary: {
+    ///             port: 5432,
+    ///         },
+    ///     },
+    /// });
+    /// let port = object.navigate(&["db", "primary", "port"]).unwrap();
+    /// assert_eq!(port.downcast_ref::<i32>(), Some(&5432));
+    /// assert!(object.navigate(&["db", "replica", "port"]).is_none());
+    /// assert!(object.navigate(&["db", "primary", "port", "x"]).is_none());
+    /// ```
+    pub fn navigate(&self, keys: &[&str]) -> Option<&dyn AnyType> {
+        let mut value: &dyn AnyType = self;
+        for key in keys {
+            value = &**value.as_object()?.map.get(*key)?;
+        }
+        Some(value)
+    }
+
+    /// Removes the entries with the largest keys until at most `max_entries` remain, returning
+    /// the removed keys in key order.
+    ///
+    /// With keys that sort by age, such as timestamp prefixes, this evicts the newest entries;
+    /// use [`Object::truncate_front`] to evict the oldest ones instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut object = object!({ a: 1, b: 2, c: 3, });
+    /// assert_eq!(object.truncate(1), ["b", "c"]);
+    /// assert_eq!(object, object!({ a: 1, }));
+    /// assert!(object.truncate(5).is_empty());
+    /// ```
+    pub fn truncate(&mut self, max_entries: usize) -> Vec<String> {
+        let mut removed = Vec::new();
+        while self.map.len() > max_entries {
+            removed.extend(self.map.pop_last().map(|(key, _)| key));
+        }
+        removed.reverse();
+        removed
+    }
+
+    /// Removes the entries with the smallest keys until at most `max_entries` remain, returning
+    /// the removed keys in key order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let mut cache = object!({
+    ///     t1000_a: 1,
+    ///     t1001_b: 2,
+    ///     t1002_c: 3,
+    /// });
+    /// assert_eq!(cache.truncate_front(2), ["t1000_a"]);
+    /// assert_eq!(cache.keys().collect::<Vec<_>>(), ["t1001_b", "t1002_c"]);
+    /// ```
+    pub fn truncate_front(&mut self, max_entries: usize) -> Vec<String> {
+        let mut removed = Vec::new();
+        while self.map.len() > max_entries {
+            removed.extend(self.map.pop_first().map(|(key, _)| key));
+        }
+        removed
+    }
+
+    /// Consumes the `Object` and applies `f` to every entry in key order, returning a new `Object`
+    /// with the results, or the first error returned by `f`.
+    ///
+    /// `f` receives the key and the value, and may return a value of another type. The result is
+    /// sealed if the `Object` was. On error, the entries processed so far and the remaining ones
+    /// are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, AnyType};
+    /// let raw = object!({
+    ///     port: "8080",
+    ///     workers: "4",
+    /// });
+    /// let parse = |key: &str, value: Box<dyn AnyType>| {
+    ///     let text = value.downcast_ref::<&str>().ok_or(format!("`{key}` is not text"))?;
+    ///     let n: u16 = text.parse().map_err(|_| format!("`{key}` is not a number"))?;
+    ///     Ok::<_, String>(Box::new(n) as Box<dyn AnyType>)
+    /// };
+    /// let parsed = raw.try_map_values(parse).unwrap();
+    /// assert_eq!(parsed.get_as::<u16>("port"), Some(&8080));
+    ///
+    /// let error = object!({ port: "http", }).try_map_values(parse).unwrap_err();
+    /// assert_eq!(error, "`port` is not a number");
+    /// ```
+    pub fn try_map_values<E>(
+        self,
+        mut f: impl FnMut(&str, Box<dyn AnyType>) -> Result<Box<dyn AnyType>, E>,
+    ) -> Result<Object, E> {
+        let map = self
+            .map
+            .into_iter()
+            .map(|(key, value)| {
+                let value = f(&key, value)?;
+                Ok((key, value))
+            })
+            .collect::<Result<_, E>>()?;
+        Ok(Object {
+            map,
+            sealed: self.sealed,
+        })
+    }
+}
+
+/// The outcome of inserting a single pair with [`Object::insert_all`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertOutcome {
+    /// The key was absent.
+    Inserted,
+    /// The key existed and its value was overwritten.
+    Overwritten,
+    /// The key was absent and the `Object` is [sealed](Object::seal), so the value was dropped.
+    Rejected,
+}
+
+/// The outcome of [`Object::try_get_or_insert_as`].
+#[derive(Debug, PartialEq)]
+pub enum GetOrInsert<'a, T> {
+    /// The key was absent and the value was inserted.
+    Inserted(&'a mut T),
+    /// The key already held a value of the requested type.
+    Found(&'a mut T),
+}
+
+impl<'a, T> GetOrInsert<'a, T> {
+    /// Returns the reference to the value, whether it was inserted or found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mut object = Object::new();
+    /// let value = object.try_get_or_insert_as("key", 1).unwrap().into_mut();
+    /// *value += 1;
+    /// assert_eq!(object.get_as::<i32>("key"), Some(&2));
+    /// ```
+    pub fn into_mut(self) -> &'a mut T {
+        match self {
+            Self::Inserted(value) | Self::Found(value) => value,
+        }
+    }
+}
+
+fn count_leaves(value: &dyn AnyType) -> usize {
+    if let Some(object) = value.downcast_ref::<Object>() {
+        object.count_leaves()
+    } else if let Some(array) = value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+        array.iter().map(|v| count_leaves(&**v)).sum()
+    } else {
+        1
+    }
+}
+
+fn depth(value: &dyn AnyType) -> usize {
+    if let Some(object) = value.downcast_ref::<Object>() {
+        object.max_depth()
+    } else if let Some(array) = value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+        1 + array.iter().map(|v| depth(&**v)).max().unwrap_or(0)
+    } else {
+        0
+    }
+}
+
+fn values_deep_eq(a: &dyn AnyType, b: &dyn AnyType) -> bool {
+    match (a.downcast_ref::<Object>(), b.downcast_ref::<Object>()) {
+        (Some(a), Some(b)) => a.deep_eq(b),
+        _ => a == b,
+    }
+}
+
+impl Object {
+    fn from_map(map: BTreeMap<String, Box<dyn AnyType>>) -> Self {
+        Object { map, sealed: false }
+    }
+}
+
+/// Formats the `Object` as `Object { map: {...} }`.
+///
+/// The formatter is passed down to every value, so `{:#?}` indents nested objects and the leaf
+/// values inside them, as in `dbg!`.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::object;
+/// let object = object!({
+///     a: {
+///         b: 1,
+///     },
+/// });
+/// assert_eq!(format!("{:?}", object), r#"Object { map: {"a": Object { map: {"b": 1} }} }"#);
+/// assert!(format!("{:#?}", object).contains("\n                \"b\": 1,\n"));
+/// ```
+impl Debug for Object {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Object").field("map", &self.map).finish()
+    }
+}
+
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl PartialOrd for Object {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.map.partial_cmp(&other.map)
+    }
+}
+
+impl Deref for Object {
+    type Target = BTreeMap<String, Box<dyn AnyType>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for Object {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+
+/// Returns the value corresponding to the key, without knowing its type.
+///
+/// Before this impl existed, `object["key"]` went through `Deref` to the `BTreeMap` and returned
+/// the `Box<dyn AnyType>`. It now returns the `dyn AnyType` inside the box, so code that called
+/// methods of `Box` on the result has to drop them, while methods of `dyn AnyType` such as
+/// `downcast_ref` work as before.
+///
+/// # Panics
+///
+/// Panics if the key is absent, with a message naming the key. Use [`Object::get`] to get an
+/// `Option` instead.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::object;
+/// let object = object!({
+///     port: 80,
+/// });
+/// assert_eq!(object["port"].downcast_ref::<i32>(), Some(&80));
+/// ```
+impl Index<&str> for Object {
+    type Output = dyn AnyType;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self.map.get(key) {
+            Some(value) => &**value,
+            None => panic!("key `{key}` is not in the `Object`"),
+        }
+    }
+}
+
+/// Returns a mutable reference to the value corresponding to the key, without knowing its type.
+///
+/// Pair it with `downcast_mut` to assign through the value.
+///
+/// # Panics
+///
+/// Panics if the key is absent.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::object;
+/// let mut object = object!({
+///     port: 80,
+/// });
+/// *object["port"].downcast_mut::<i32>().unwrap() = 8080;
+/// assert_eq!(object.get_as::<i32>("port"), Some(&8080));
+/// ```
+impl IndexMut<&str> for Object {
+    fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+        match self.map.get_mut(key) {
+            Some(value) => &mut **value,
+            None => panic!("key `{key}` is not in the `Object`"),
+        }
+    }
+}
+
+/// Boxes every value of a `HashMap` of a single type.
+///
+/// Since `Box<dyn AnyType>` is itself an `AnyType`, a map of already boxed values would be boxed
+/// again, so that `get_as` can no longer see the inner type. Use [`Object::from_boxed_map`] for
+/// those instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use dynamic_object::Object;
+/// let object = Object::from(HashMap::from([("a".to_string(), 1)]));
+/// assert_eq!(object.get_as::<i32>("a"), Some(&1));
+/// ```
+impl<T: AnyType> From<HashMap<String, T>> for Object {
+    fn from(map: HashMap<String, T>) -> Self {
+        Object::from_map(
+            map.into_iter()
+                .map(|(k, v)| (k, Box::new(v) as Box<dyn AnyType>))
+                .collect(),
+        )
+    }
+}
+
+/// Boxes every value of a `BTreeMap` of a single type.
+///
+/// Since `Box<dyn AnyType>` is itself an `AnyType`, a map of already boxed values, such as the one
+/// an `Object` derefs to, would be boxed again, so that `get_as` can no longer see the inner type.
+/// Use [`Object::from_boxed_map`] for those instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use dynamic_object::Object;
+/// let object = Object::from(BTreeMap::from([("a".to_string(), "x")]));
+/// assert_eq!(object.get_as::<&str>("a"), Some(&"x"));
+/// ```
+impl<T: AnyType> From<BTreeMap<String, T>> for Object {
+    fn from(map: BTreeMap<String, T>) -> Self {
+        Object::from_map(
+            map.into_iter()
+                .map(|(k, v)| (k, Box::new(v) as Box<dyn AnyType>))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
-    #[derive(Debug, PartialEq, PartialOrd)]
-    struct Foo {
-        bar: i32,
+    #[derive(Debug, PartialEq, PartialOrd)]
+    struct Foo {
+        bar: i32,
+    }
+
+    #[test]
+    fn works_with_empty_object() {
+        let mut empty = object!({});
+        assert_eq!(empty.get_as::<&str>("foo"), None);
+        empty.insert("foo", "bar");
+        assert_eq!(empty.get_as::<&str>("foo"), Some(&"bar"));
+    }
+
+    #[test]
+    fn works_with_simple_object() {
+        let x = object!({
+            foo: Foo { bar: 123 },
+            bar: 123,
+        });
+        assert_eq!(x.get_as::<Foo>("foo"), Some(&Foo { bar: 123 }));
+        assert_eq!(x.get_as::<i32>("bar"), Some(&123));
+    }
+
+    #[test]
+    fn works_with_nested_object() {
+        let y = object!({
+            foo: "fpp",
+            bar: 123,
+            baz: {
+                inner: "value",
+            },
+        });
+        assert_eq!(y.get_as::<&str>("foo"), Some(&"fpp"));
+        assert_eq!(y.get_as::<i32>("bar"), Some(&123));
+        let baz = y.get_as::<Object>("baz");
+        assert!(baz.is_some());
+        let baz_inner = baz.and_then(|v| v.get_as::<&str>("inner"));
+        assert!(baz_inner.is_some());
+        assert_eq!(baz_inner, Some(&"value"));
+    }
+
+    #[test]
+    fn works_with_deeply_nested_object() {
+        let z = object!({
+            a: "xyz",
+            b: 69,
+            c: {
+                inner: "value",
+                another: {
+                    inner: 420,
+                    d: "tr4vvyr00lz",
+                },
+            },
+        });
+        assert_eq!(z.get_as::<&str>("a"), Some(&"xyz"));
+        assert_eq!(z.get_as::<i32>("b"), Some(&69));
+        let c = z.get_as::<Object>("c");
+        assert!(c.is_some());
+        let c_inner = c.and_then(|v| v.get_as::<&str>("inner"));
+        assert_eq!(c_inner, Some(&"value"));
+        let c_inner_another = c.and_then(|v| v.get_as::<Object>("another"));
+        assert!(c_inner_another.is_some());
+        let c_inner_another_inner = c_inner_another.and_then(|v| v.get_as::<i32>("inner"));
+        assert_eq!(c_inner_another_inner, Some(&420));
+        let c_inner_another_d = c_inner_another.and_then(|v| v.get_as::<&str>("d"));
+        assert_eq!(c_inner_another_d, Some(&"tr4vvyr00lz"));
+    }
+
+    #[test]
+    fn works_with_mixed_object() {
+        let mixed = object!({
+            a: "xyz",
+            b: 69,
+            c: {
+                inner: "value",
+                another: {
+                    inner: 420,
+                    d: "tr4vvyr00lz",
+                },
+            },
+            d: Foo { bar: 123 },
+        });
+        assert_eq!(mixed.get_as::<&str>("a"), Some(&"xyz"));
+        assert_eq!(mixed.get_as::<i32>("b"), Some(&69));
+        let c = mixed.get_as::<Object>("c");
+        assert!(c.is_some());
+        let c_inner = c.and_then(|v| v.get_as::<&str>("inner"));
+        assert_eq!(c_inner, Some(&"value"));
+        let c_inner_another = c.and_then(|v| v.get_as::<Object>("another"));
+        assert!(c_inner_another.is_some());
+        let c_inner_another_inner = c_inner_another.and_then(|v| v.get_as::<i32>("inner"));
+        assert_eq!(c_inner_another_inner, Some(&420));
+        let c_inner_another_d = c_inner_another.and_then(|v| v.get_as::<&str>("d"));
+        assert_eq!(c_inner_another_d, Some(&"tr4vvyr00lz"));
+        assert_eq!(mixed.get_as::<Foo>("d"), Some(&Foo { bar: 123 }));
+    }
+
+    #[test]
+    fn works_like_a_map() {
+        let mut map = object!({});
+        assert!(map.get("foo").is_none());
+        map.insert("foo", "bar");
+        map.get_or_insert_as::<&str>("foo", "lol");
+        assert_eq!(
+            map.get("foo").and_then(|v| v.downcast_ref::<&str>()),
+            Some(&"bar")
+        );
+        map.get_or_insert_as::<&str>("bar", "baz");
+        assert_eq!(
+            map.get("bar").and_then(|v| v.downcast_ref::<&str>()),
+            Some(&"baz")
+        );
+    }
+
+    #[test]
+    fn merge_with_resolves_conflicts() {
+        let mut a = object!({
+            foo: "bar",
+            baz: 1,
+        });
+        let b = object!({
+            foo: "qux",
+            baz: 2,
+            new: 3,
+        });
+        let mut conflicts = Vec::new();
+        a.merge_with(b, |key, ours, theirs| {
+            conflicts.push(key.to_string());
+            if let (Some(x), Some(y)) = (ours.downcast_ref::<i32>(), theirs.downcast_ref::<i32>()) {
+                return Box::new(x + y);
+            }
+            ours
+        });
+        assert_eq!(conflicts, vec!["baz", "foo"]);
+        assert_eq!(a.get_as::<&str>("foo"), Some(&"bar"));
+        assert_eq!(a.get_as::<i32>("baz"), Some(&3));
+        assert_eq!(a.get_as::<i32>("new"), Some(&3));
+    }
+
+    #[test]
+    fn insert_all_reports_outcomes() {
+        let mut obj = object!({
+            foo: "bar",
+        });
+        let outcomes = obj.insert_all(vec![("foo", 1), ("baz", 2), ("baz", 3)]);
+        assert_eq!(
+            outcomes,
+            vec![
+                ("foo".to_string(), InsertOutcome::Overwritten),
+                ("baz".to_string(), InsertOutcome::Inserted),
+                ("baz".to_string(), InsertOutcome::Overwritten),
+            ]
+        );
+        assert_eq!(obj.get_as::<i32>("foo"), Some(&1));
+        assert_eq!(obj.get_as::<i32>("baz"), Some(&3));
+    }
+
+    #[test]
+    fn partition_by_type_moves_entries() {
+        let obj = object!({
+            a: 1,
+            b: "two",
+            c: Foo { bar: 3 },
+            d: {
+                e: 4,
+            },
+            f: 5,
+        });
+        let parts = obj.partition_by_type();
+        let names = parts.keys().copied().collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "&str",
+                "dynamic_object::Object",
+                "dynamic_object::tests::Foo",
+                "i32"
+            ]
+        );
+        let ints = parts["i32"].keys().cloned().collect::<Vec<_>>();
+        assert_eq!(ints, vec!["a", "f"]);
+        assert_eq!(
+            parts["dynamic_object::tests::Foo"].get_as::<Foo>("c"),
+            Some(&Foo { bar: 3 })
+        );
+        let nested = parts["dynamic_object::Object"].get_as::<Object>("d");
+        assert_eq!(nested.and_then(|d| d.get_as::<i32>("e")), Some(&4));
+    }
+
+    #[test]
+    fn get_as_slice_borrows_vecs() {
+        let mut obj = object!({
+            nums: vec![1, 2, 3],
+            name: "foo",
+        });
+        assert_eq!(obj.get_as_slice::<i32>("nums"), Some(&[1, 2, 3][..]));
+        assert_eq!(obj.get_as_slice::<i64>("nums"), None);
+        assert_eq!(obj.get_as_slice::<i32>("name"), None);
+        obj.get_as_slice_mut::<i32>("nums").unwrap()[0] = 10;
+        assert_eq!(obj.get_as::<Vec<i32>>("nums"), Some(&vec![10, 2, 3]));
+    }
+
+    #[test]
+    fn get_or_insert_as_with_existing_wrong_type() {
+        let mut obj = object!({
+            foo: "bar",
+        });
+        assert_eq!(obj.get_or_insert_as::<i32>("foo", 1), None);
+        assert_eq!(obj.get_as::<&str>("foo"), Some(&"bar"));
+
+        assert_eq!(
+            obj.try_get_or_insert_as::<i32>("foo", 1),
+            Err(InsertError::TypeConflict(TypeConflict {
+                key: "foo".to_string(),
+                expected: "i32",
+                found: "&str",
+            }))
+        );
+        assert_eq!(obj.get_as::<&str>("foo"), Some(&"bar"));
+    }
+
+    #[test]
+    fn try_get_or_insert_as_reports_outcome() {
+        let mut obj = Object::new();
+        assert_eq!(
+            obj.try_get_or_insert_as("foo", 1),
+            Ok(GetOrInsert::Inserted(&mut 1))
+        );
+        assert_eq!(
+            obj.try_get_or_insert_as("foo", 2),
+            Ok(GetOrInsert::Found(&mut 1))
+        );
+        assert_eq!(obj.get_as::<i32>("foo"), Some(&1));
+    }
+
+    #[test]
+    fn test_equality() {
+        let a = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let b = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        assert_eq!(a, b);
+
+        let a = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let b = object!({
+            foo: "bar",
+            baz: 456,
+        });
+        assert_ne!(a, b);
+
+        let a = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let b = object!({
+            foo: "bar",
+            baz: 123,
+            qux: "quux",
+        });
+        assert_ne!(a, b);
+
+        let a = object!({
+            foo: "bar",
+            baz: {
+                qux: "quux",
+            },
+        });
+        let b = object!({
+            foo: "bar",
+            baz: {
+                qux: "quux",
+            },
+        });
+        assert_eq!(a, b);
+
+        let a = object!({
+            foo: Foo { bar: 123 },
+        });
+        let b = object!({
+            foo: Foo { bar: 123 },
+        });
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let a = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let b = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+
+        let a = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let b = object!({
+            foo: "bar",
+            baz: 456,
+        });
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
+
+        let a = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let b = object!({
+            foo: "bar",
+            baz: 123,
+            qux: "quux",
+        });
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
+
+        let a = object!({
+            foo: "bar",
+            baz: {
+                qux: "quux",
+            },
+        });
+        let b = object!({
+            foo: "bar",
+            baz: {
+                qux: "quux",
+            },
+        });
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+
+        let a = object!({
+            foo: Foo { bar: 123 },
+        });
+        let b = object!({
+            foo: Foo { bar: 123 },
+        });
+        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn test_debug() {
+        let obj = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        assert_eq!(
+            format!("{:?}", obj),
+            r#"Object { map: {"baz": 123, "foo": "bar"} }"#
+        );
+    }
+
+    #[test]
+    fn preserves_order() {
+        let obj = object!({
+            foo: "bar",
+            baz: 123,
+        });
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["baz", "foo"]);
+
+        let obj = object!({
+            foo: "bar",
+            baz: 123,
+            qux: "quux",
+        });
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["baz", "foo", "qux"]);
+
+        let obj = object!({
+            foo: "bar",
+            baz: {
+                qux: "quux",
+            },
+        });
+
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        let entries = obj.iter().collect::<Vec<_>>();
+
+        for (i, (k, v)) in obj.iter().enumerate() {
+            let key = keys[i].clone();
+            assert_eq!(k, &key);
+            assert_eq!(entries[i].0, &key);
+            assert_eq!(entries[i].1, v);
+        }
+    }
+
+    #[test]
+    fn rename_keys_detects_collisions() {
+        let mut obj = object!({
+            a: 1,
+            b: 2,
+            c: 3,
+        });
+        let mapping = BTreeMap::from([
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "a".to_string()),
+            ("missing".to_string(), "z".to_string()),
+        ]);
+        obj.rename_keys(&mapping).unwrap();
+        assert_eq!(obj.get_as::<i32>("a"), Some(&2));
+        assert_eq!(obj.get_as::<i32>("b"), Some(&1));
+        assert!(obj.get("z").is_none());
+
+        let mapping = BTreeMap::from([
+            ("a".to_string(), "x".to_string()),
+            ("b".to_string(), "x".to_string()),
+        ]);
+        assert_eq!(
+            obj.rename_keys(&mapping),
+            Err(KeyCollision {
+                key: "x".to_string()
+            })
+        );
+
+        let mapping = BTreeMap::from([("a".to_string(), "c".to_string())]);
+        assert_eq!(
+            obj.rename_keys(&mapping),
+            Err(KeyCollision {
+                key: "c".to_string()
+            })
+        );
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
     }
 
     #[test]
-    fn works_with_empty_object() {
-        let mut empty = object!({});
-        assert_eq!(empty.get_as::<&str>("foo"), None);
-        empty.insert("foo", "bar");
-        assert_eq!(empty.get_as::<&str>("foo"), Some(&"bar"));
+    fn deep_eq_recurses_and_is_strict() {
+        let a = object!({
+            foo: "bar",
+            baz: {
+                qux: Foo { bar: 1 },
+            },
+        });
+        let b = object!({
+            foo: "bar",
+            baz: {
+                qux: Foo { bar: 1 },
+            },
+        });
+        assert!(a.deep_eq(&b));
+
+        let c = object!({
+            foo: "bar",
+            baz: {
+                qux: Foo { bar: 2 },
+            },
+        });
+        assert!(!a.deep_eq(&c));
+
+        assert!(!object!({ n: 1i32, }).deep_eq(&object!({ n: 1i64, })));
+        assert!(!object!({ n: f64::NAN, }).deep_eq(&object!({ n: f64::NAN, })));
+        assert!(object!({ n: 0.0, }).deep_eq(&object!({ n: -0.0, })));
+        assert!(!a.deep_eq(&object!({ foo: "bar", })));
     }
 
     #[test]
-    fn works_with_simple_object() {
-        let x = object!({
-            foo: Foo { bar: 123 },
-            bar: 123,
+    fn prune_empty_removes_only_empty_objects() {
+        let mut obj = object!({
+            a: {
+                b: {
+                    c: {},
+                },
+                d: {},
+            },
+            e: {
+                f: String::new(),
+                g: {},
+            },
+            h: Vec::<i32>::new(),
         });
-        assert_eq!(x.get_as::<Foo>("foo"), Some(&Foo { bar: 123 }));
-        assert_eq!(x.get_as::<i32>("bar"), Some(&123));
+        obj.prune_empty();
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["e", "h"]);
+        let e = obj.get_as::<Object>("e").unwrap();
+        let keys: Vec<_> = e.keys().cloned().collect();
+        assert_eq!(keys, vec!["f"]);
     }
 
     #[test]
-    fn works_with_nested_object() {
-        let y = object!({
-            foo: "fpp",
-            bar: 123,
-            baz: {
+    fn works_with_conditional_fields() {
+        let yes = true;
+        let no = false;
+        let items = [1, 2, 3];
+        let obj = object!({
+            a: 1,
+            b: 2 if yes,
+            c: 3 if no,
+            d: items.len() if yes && !no,
+            e: {
                 inner: "value",
+            } if yes,
+            f: {
+                inner: "value",
+            } if no,
+            g: (if no { 1 } else { 2 }) if yes,
+        });
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["a", "b", "d", "e", "g"]);
+        assert_eq!(obj.get_as::<usize>("d"), Some(&3));
+        assert_eq!(obj.get_as::<i32>("g"), Some(&2));
+        let e = obj.get_as::<Object>("e");
+        assert_eq!(e.and_then(|e| e.get_as::<&str>("inner")), Some(&"value"));
+    }
+
+    #[test]
+    fn eq_ignoring_skips_dotted_paths() {
+        let a = object!({
+            id: 1,
+            name: "foo",
+            meta: {
+                created_at: 1,
+                updated_at: 2,
             },
         });
-        assert_eq!(y.get_as::<&str>("foo"), Some(&"fpp"));
-        assert_eq!(y.get_as::<i32>("bar"), Some(&123));
-        let baz = y.get_as::<Object>("baz");
-        assert!(baz.is_some());
-        let baz_inner = baz.and_then(|v| v.get_as::<&str>("inner"));
-        assert!(baz_inner.is_some());
-        assert_eq!(baz_inner, Some(&"value"));
+        let b = object!({
+            name: "foo",
+            meta: {
+                created_at: 1,
+                updated_at: 3,
+            },
+            extra: true,
+        });
+        assert!(a.eq_ignoring(&b, &["id", "extra", "meta.updated_at"]));
+        assert!(!a.eq_ignoring(&b, &["id", "meta.updated_at"]));
+        assert!(!a.eq_ignoring(&b, &["extra", "meta.updated_at"]));
+        assert!(!a.eq_ignoring(&b, &["id", "extra", "updated_at"]));
+        assert!(a.eq_ignoring(&b, &["id", "extra", "meta"]));
     }
 
     #[test]
-    fn works_with_deeply_nested_object() {
-        let z = object!({
-            a: "xyz",
-            b: 69,
+    fn drain_as_moves_matching_values_out() {
+        let mut obj = object!({
+            a: Foo { bar: 1 },
+            b: 2,
+            c: Foo { bar: 3 },
+        });
+        let drained: Vec<_> = obj.drain_as::<Foo>().collect();
+        assert_eq!(
+            drained,
+            vec![
+                ("a".to_string(), Foo { bar: 1 }),
+                ("c".to_string(), Foo { bar: 3 }),
+            ]
+        );
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["b"]);
+        assert_eq!(obj.drain_as::<Foo>().count(), 0);
+    }
+
+    #[test]
+    fn path_entry_as_creates_and_updates() {
+        let mut obj = object!({
+            a: {
+                b: 1,
+            },
+            leaf: "value",
+        });
+        *obj.path_entry_as("a.b", || 0).unwrap() += 1;
+        obj.path_entry_as("x.y.z", Vec::<i32>::new).unwrap().push(1);
+        let a = obj.get_as::<Object>("a").unwrap();
+        assert_eq!(a.get_as::<i32>("b"), Some(&2));
+        let y = obj
+            .get_as::<Object>("x")
+            .and_then(|x| x.get_as::<Object>("y"))
+            .unwrap();
+        assert_eq!(y.get_as::<Vec<i32>>("z"), Some(&vec![1]));
+
+        assert_eq!(
+            obj.path_entry_as("leaf.inner", || 0),
+            Err(PathError::NotAnObject {
+                path: "leaf".to_string()
+            })
+        );
+        assert_eq!(
+            obj.path_entry_as("a.b", || "text"),
+            Err(PathError::WrongType {
+                path: "a.b".to_string(),
+                expected: "&str",
+                found: "i32",
+            })
+        );
+        assert_eq!(obj.path_entry_as("top", || 5), Ok(&mut 5));
+    }
+
+    #[test]
+    fn map_values_as_transforms_one_type() {
+        let mut obj = object!({
+            a: 1,
+            b: 2,
+            c: "three",
+            d: {
+                e: 4,
+            },
+        });
+        obj.map_values_as(|n: &i32| Foo { bar: n * 10 });
+        assert_eq!(obj.get_as::<Foo>("a"), Some(&Foo { bar: 10 }));
+        assert_eq!(obj.get_as::<Foo>("b"), Some(&Foo { bar: 20 }));
+        assert_eq!(obj.get_as::<&str>("c"), Some(&"three"));
+        let d = obj.get_as::<Object>("d").unwrap();
+        assert_eq!(d.get_as::<i32>("e"), Some(&4));
+    }
+
+    #[test]
+    fn round_trips_object_through_any() {
+        let mut outer = object!({
+            inner: {
+                foo: "bar",
+            },
+        });
+        let any = outer.get("inner").map(|v| (**v).as_any()).unwrap();
+        let inner = any.downcast_ref::<Object>().unwrap();
+        assert_eq!(inner.as_any().downcast_ref::<Object>(), Some(inner));
+        assert_eq!(inner.get_as::<&str>("foo"), Some(&"bar"));
+
+        let inner = outer
+            .get_mut("inner")
+            .and_then(|v| (**v).as_any_mut().downcast_mut::<Object>())
+            .unwrap();
+        inner
+            .as_any_mut()
+            .downcast_mut::<Object>()
+            .unwrap()
+            .insert("baz", 1);
+        let inner = outer.get_as::<Object>("inner").unwrap();
+        assert_eq!(inner.get_as::<i32>("baz"), Some(&1));
+    }
+
+    #[test]
+    fn retain_paths_keeps_allowed_leaves() {
+        let mut obj = object!({
+            a: 1,
+            b: 2,
             c: {
-                inner: "value",
-                another: {
-                    inner: 420,
-                    d: "tr4vvyr00lz",
+                d: 3,
+                e: {
+                    f: 4,
+                    g: 5,
                 },
+                empty: {},
+            },
+            h: {
+                i: 6,
+            },
+            hh: 7,
+            j: {
+                k: 8,
             },
         });
-        assert_eq!(z.get_as::<&str>("a"), Some(&"xyz"));
-        assert_eq!(z.get_as::<i32>("b"), Some(&69));
-        let c = z.get_as::<Object>("c");
-        assert!(c.is_some());
-        let c_inner = c.and_then(|v| v.get_as::<&str>("inner"));
-        assert_eq!(c_inner, Some(&"value"));
-        let c_inner_another = c.and_then(|v| v.get_as::<Object>("another"));
-        assert!(c_inner_another.is_some());
-        let c_inner_another_inner = c_inner_another.and_then(|v| v.get_as::<i32>("inner"));
-        assert_eq!(c_inner_another_inner, Some(&420));
-        let c_inner_another_d = c_inner_another.and_then(|v| v.get_as::<&str>("d"));
-        assert_eq!(c_inner_another_d, Some(&"tr4vvyr00lz"));
+        obj.retain_paths(&["a", "c.e.f", "h.*", "j"]);
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["a", "c", "h", "j"]);
+        let c = obj.get_as::<Object>("c").unwrap();
+        let keys: Vec<_> = c.keys().cloned().collect();
+        assert_eq!(keys, vec!["e"]);
+        let e = c.get_as::<Object>("e").unwrap();
+        let keys: Vec<_> = e.keys().cloned().collect();
+        assert_eq!(keys, vec!["f"]);
+        let j = obj.get_as::<Object>("j").unwrap();
+        assert_eq!(j.get_as::<i32>("k"), Some(&8));
+
+        obj.retain_paths(&["*"]);
+        assert_eq!(obj.len(), 4);
+        obj.retain_paths(&[]);
+        assert!(obj.is_empty());
+    }
+
+    #[test]
+    fn insert_checked_rejects_type_changes() {
+        let mut obj = object!({
+            foo: Foo { bar: 1 },
+        });
+        assert_eq!(obj.insert_checked("foo", Foo { bar: 2 }), Ok(()));
+        assert_eq!(obj.get_as::<Foo>("foo"), Some(&Foo { bar: 2 }));
+        assert_eq!(
+            obj.insert_checked("foo", 3),
+            Err(InsertError::TypeConflict(TypeConflict {
+                key: "foo".to_string(),
+                expected: "i32",
+                found: "dynamic_object::tests::Foo",
+            }))
+        );
+        assert_eq!(obj.get_as::<Foo>("foo"), Some(&Foo { bar: 2 }));
+        assert_eq!(obj.insert_checked("bar", 3), Ok(()));
+        assert_eq!(obj.get_as::<i32>("bar"), Some(&3));
+    }
+
+    #[test]
+    fn iter_objects_yields_nested_objects() {
+        let mut obj = object!({
+            a: {
+                n: 1,
+            },
+            b: "not an object",
+            c: {
+                n: 2,
+            },
+        });
+        let sums: Vec<_> = obj
+            .iter_objects()
+            .map(|(key, object)| (key.clone(), object.get_as::<i32>("n").copied()))
+            .collect();
+        assert_eq!(
+            sums,
+            vec![("a".to_string(), Some(1)), ("c".to_string(), Some(2))]
+        );
+        for (_, object) in obj.iter_objects_mut() {
+            object.clear();
+        }
+        assert!(obj.iter_objects().all(|(_, object)| object.is_empty()));
+        assert_eq!(obj.get_as::<&str>("b"), Some(&"not an object"));
+    }
+
+    #[test]
+    fn optional_values_are_transparent() {
+        let mut obj = object!({
+            stale: Foo { bar: 0 },
+        });
+        obj.insert_optional("present", Some(Foo { bar: 1 }));
+        obj.insert_optional("absent", None::<Foo>);
+        obj.insert_optional("stale", None::<Foo>);
+        assert_eq!(obj.get_as::<Foo>("present"), Some(&Foo { bar: 1 }));
+        assert!(!obj.contains_key("absent"));
+        assert!(!obj.contains_key("stale"));
+
+        obj.insert("wrapped", Some(Foo { bar: 2 }));
+        obj.insert("empty", None::<Foo>);
+        assert_eq!(obj.get_optional_as::<Foo>("present"), Some(&Foo { bar: 1 }));
+        assert_eq!(obj.get_optional_as::<Foo>("wrapped"), Some(&Foo { bar: 2 }));
+        assert_eq!(obj.get_optional_as::<Foo>("empty"), None);
+        assert_eq!(obj.get_optional_as::<Foo>("absent"), None);
+        assert_eq!(obj.get_optional_as::<i32>("present"), None);
+    }
+
+    #[test]
+    fn merge_preferring_non_null_unsets_keys() {
+        let mut obj = object!({
+            a: 1,
+            b: 2,
+            c: 3,
+        });
+        obj.merge_preferring_non_null(object!({
+            b: Null,
+            c: "three",
+            d: Null,
+            e: 5,
+        }));
+        let keys: Vec<_> = obj.keys().cloned().collect();
+        assert_eq!(keys, vec!["a", "c", "e"]);
+        assert_eq!(obj.get_as::<&str>("c"), Some(&"three"));
+        assert_eq!(obj.get_as::<i32>("e"), Some(&5));
+    }
+
+    #[test]
+    fn values_can_be_viewed_as_objects() {
+        let mut obj = object!({
+            a: {
+                b: {
+                    c: 1,
+                },
+            },
+            d: 2,
+        });
+        let c = obj
+            .get("a")
+            .and_then(|v| v.as_object())
+            .and_then(|a| a.get("b"))
+            .and_then(|v| v.as_object())
+            .and_then(|b| b.get_as::<i32>("c"));
+        assert_eq!(c, Some(&1));
+        assert!(obj.get("d").and_then(|v| v.as_object()).is_none());
+
+        obj.get_mut("a")
+            .and_then(|v| v.as_object_mut())
+            .unwrap()
+            .insert("e", 3);
+        let a = obj.get("a").and_then(|v| v.as_object()).unwrap();
+        assert_eq!(a.get_as::<i32>("e"), Some(&3));
+    }
+
+    #[test]
+    fn cmp_total_orders_everything() {
+        let nan = object!({
+            a: f64::NAN,
+        });
+        let one = object!({
+            a: 1.0,
+        });
+        assert_eq!(nan.cmp_total(&nan), Ordering::Equal);
+        assert_eq!(one.cmp_total(&nan), Ordering::Less);
+        assert_eq!(
+            object!({ a: -0.0f32, }).cmp_total(&object!({ a: 0.0f32, })),
+            Ordering::Less
+        );
+
+        let nested = object!({
+            a: {
+                b: f64::NAN,
+            },
+        });
+        assert_eq!(nested.cmp_total(&nested), Ordering::Equal);
+
+        let list = |values: Vec<f64>| object!({ a: values });
+        let (nans, ones, twos) = (list(vec![f64::NAN]), list(vec![1.0]), list(vec![2.0]));
+        assert_eq!(ones.cmp_total(&twos), Ordering::Less);
+        assert_eq!(twos.cmp_total(&nans), Ordering::Less);
+        assert_eq!(ones.cmp_total(&nans), Ordering::Less);
+        assert_eq!(nans.cmp_total(&list(vec![f64::NAN])), Ordering::Equal);
+        assert_eq!(
+            object!({ a: Some(f64::NAN) }).cmp_total(&object!({ a: Some(f64::INFINITY) })),
+            Ordering::Greater
+        );
+
+        let int = object!({
+            a: 1i32,
+        });
+        let text = object!({
+            a: "1",
+        });
+        assert_eq!(text.cmp_total(&int), Ordering::Less);
+        assert_eq!(int.cmp_total(&text), Ordering::Greater);
+
+        let shorter = object!({
+            a: 1,
+        });
+        let longer = object!({
+            a: 1,
+            b: 2,
+        });
+        assert_eq!(shorter.cmp_total(&longer), Ordering::Less);
+        assert_eq!(
+            object!({ a: 2, }).cmp_total(&object!({ b: 1, })),
+            Ordering::Less
+        );
+
+        let mut objects = [one, nan, int, text];
+        objects.sort_by(Object::cmp_total);
+        let firsts: Vec<_> = objects
+            .iter()
+            .map(|o| (**o.get("a").unwrap()).dyn_type_name())
+            .collect();
+        assert_eq!(firsts, vec!["&str", "f64", "f64", "i32"]);
+    }
+
+    #[test]
+    fn test_fluent_insert() {
+        let mut object = Object::new()
+            .with("a", 1)
+            .with("a", 2)
+            .with("b", Foo { bar: 3 });
+        object.set("c", 4).set("b", 5);
+        assert_eq!(object.len(), 3);
+        assert_eq!(object.get_as::<i32>("a"), Some(&2));
+        assert_eq!(object.get_as::<i32>("b"), Some(&5));
+        assert_eq!(object.get_as::<i32>("c"), Some(&4));
+    }
+
+    #[test]
+    fn test_get_as_cow() {
+        let object = object!({
+            name: "name".to_string(),
+            count: 1,
+        });
+        let mut name = object.get_as_cow::<String>("name").unwrap();
+        name.to_mut().push('!');
+        assert_eq!(name.as_str(), "name!");
+        assert_eq!(object.get_as::<String>("name").unwrap(), "name");
+        assert!(object.get_as_cow::<String>("count").is_none());
+        assert!(object.get_as_cow::<i32>("missing").is_none());
+    }
+
+    #[test]
+    fn test_sorted_entries() {
+        let object = object!({
+            c: 3,
+            a: Foo { bar: 1 },
+            b: "two",
+        });
+        let entries = object.sorted_entries();
+        let keys: Vec<_> = entries.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+        assert_eq!(entries[0].1.downcast_ref::<Foo>(), Some(&Foo { bar: 1 }));
+        assert_eq!(entries[2].1.downcast_ref::<i32>(), Some(&3));
+    }
+
+    #[test]
+    fn test_get_debug() {
+        let object = object!({
+            foo: Foo { bar: 1 },
+            nested: {
+                text: "hi",
+            },
+        });
+        assert_eq!(
+            format!("{:?}", object.get_debug("foo").unwrap()),
+            "Foo { bar: 1 }"
+        );
+        assert_eq!(
+            format!("{:?}", object.get_debug("nested").unwrap()),
+            "Object { map: {\"text\": \"hi\"} }"
+        );
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut object = object!({
+            t2023_12: 1,
+            t2024_01: Foo { bar: 2 },
+            t2024_02: 3,
+        });
+        let newer = object.split_off("t2024");
+        assert_eq!(object.keys().collect::<Vec<_>>(), ["t2023_12"]);
+        assert_eq!(newer.get_as::<Foo>("t2024_01"), Some(&Foo { bar: 2 }));
+        assert_eq!(newer.get_as::<i32>("t2024_02"), Some(&3));
+
+        let empty = object.split_off("u");
+        assert!(empty.is_empty());
+        assert_eq!(object.len(), 1);
+    }
+
+    #[test]
+    fn test_from_homogeneous_maps() {
+        let query = HashMap::from([
+            ("page".to_string(), "2".to_string()),
+            ("sort".to_string(), "name".to_string()),
+        ]);
+        let object = Object::from(query);
+        assert_eq!(object.len(), 2);
+        assert_eq!(object.get_as::<String>("page").unwrap(), "2");
+
+        let object: Object = BTreeMap::from([("a".to_string(), Foo { bar: 1 })]).into();
+        assert_eq!(object.get_as::<Foo>("a"), Some(&Foo { bar: 1 }));
+    }
+
+    #[test]
+    fn test_entry_ref_as() {
+        let mut object = object!({
+            name: "name",
+        });
+        let mut calls = 0;
+        for _ in 0..3 {
+            let count = object
+                .entry_ref_as("count", || {
+                    calls += 1;
+                    0
+                })
+                .unwrap()
+                .into_mut();
+            *count += 1;
+        }
+        assert_eq!(calls, 1);
+        assert_eq!(object.get_as::<i32>("count"), Some(&3));
+
+        let conflict = object.entry_ref_as("name", || 0).unwrap_err();
+        assert_eq!(
+            conflict,
+            InsertError::TypeConflict(TypeConflict {
+                key: "name".to_string(),
+                expected: "i32",
+                found: "&str",
+            })
+        );
+        assert_eq!(object.get_as::<&str>("name"), Some(&"name"));
+    }
+
+    #[test]
+    fn test_seal() {
+        let mut object = object!({
+            name: "name",
+            count: 1,
+        });
+        object.seal();
+        object.insert("name", "renamed");
+        object.insert("extra", 2);
+        object.set("count", 3).set("other", 4);
+        object.insert_optional("missing", Some(5));
+        assert!(!object.try_insert("extra", 6));
+        *object.get_mut_as::<i32>("count").unwrap() += 1;
+        assert_eq!(object.keys().collect::<Vec<_>>(), ["count", "name"]);
+        assert_eq!(object.get_as::<&str>("name"), Some(&"renamed"));
+        assert_eq!(object.get_as::<i32>("count"), Some(&4));
+
+        let unsealed = Object::new().with("a", 1);
+        let mut sealed = Object::new().with("a", 1);
+        sealed.seal();
+        assert_eq!(sealed, unsealed);
+        assert_eq!(format!("{:?}", sealed), format!("{:?}", unsealed));
+    }
+
+    #[test]
+    fn test_contains_value() {
+        let object = object!({
+            foo: Foo { bar: 1 },
+            nan: f64::NAN,
+            nested: {
+                a: 1,
+            },
+        });
+        assert!(object.contains_value(&Foo { bar: 1 }));
+        assert!(!object.contains_value(&Foo { bar: 2 }));
+        assert!(!object.contains_value(&f64::NAN));
+        assert_eq!(
+            object.find_key_of(&object!({ a: 1, })).map(String::as_str),
+            Some("nested")
+        );
+    }
+
+    #[test]
+    fn test_type_id_of() {
+        let object = object!({
+            foo: Foo { bar: 1 },
+            nested: {},
+        });
+        let routes = BTreeMap::from([
+            (TypeId::of::<Foo>(), "foo"),
+            (TypeId::of::<Object>(), "object"),
+        ]);
+        let routed: Vec<_> = object
+            .keys()
+            .map(|k| routes[&object.type_id_of(k).unwrap()])
+            .collect();
+        assert_eq!(routed, ["foo", "object"]);
+        assert_ne!(
+            object.type_id_of("foo"),
+            Some(TypeId::of::<Box<dyn AnyType>>())
+        );
+    }
+
+    #[test]
+    fn test_strip_prefix() {
+        let mut object = Object::new();
+        object.insert("db", "kept");
+        object.insert("db.", "kept");
+        object.insert("db.host", "localhost");
+        object.insert("db.pool.size", 4);
+        object.insert("dbx.host", "kept");
+        object.insert("cache.host", "kept");
+        let db = object.strip_prefix("db");
+        assert_eq!(db.keys().collect::<Vec<_>>(), ["host", "pool.size"]);
+        assert_eq!(db.get_as::<i32>("pool.size"), Some(&4));
+        assert_eq!(
+            object.keys().collect::<Vec<_>>(),
+            ["cache.host", "db", "db.", "dbx.host"]
+        );
+        assert!(object.strip_prefix("missing").is_empty());
+    }
+
+    #[test]
+    fn test_retain_leaves() {
+        let mut object = object!({
+            a: 1,
+            b: "two",
+            c: {
+                d: 3,
+                e: {
+                    f: "six",
+                },
+            },
+            g: {},
+        });
+        let mut seen = Vec::new();
+        object.retain_leaves(|path, value| {
+            seen.push(path.to_string());
+            value.downcast_ref::<i32>().is_some()
+        });
+        assert_eq!(seen, ["a", "b", "c.d", "c.e.f"]);
+        assert_eq!(
+            object,
+            object!({
+                a: 1,
+                c: {
+                    d: 3,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_structural_metrics() {
+        let mut object = Object::new();
+        for _ in 0..50 {
+            object = Object::new().with("child", object).with("leaf", 1);
+        }
+        assert_eq!(object.count_leaves(), 50);
+        assert_eq!(object.max_depth(), 51);
+
+        let flat = object!({
+            a: 1,
+            b: Vec::<Box<dyn AnyType>>::new(),
+            c: vec![1, 2, 3],
+        });
+        assert_eq!(flat.count_leaves(), 2);
+        assert_eq!(flat.max_depth(), 2);
+    }
+
+    #[test]
+    fn test_sanitize_keys() {
+        let mut inner = Object::new();
+        inner.insert("inner key", 2);
+        inner.insert("inner-key", 3);
+        let mut object = Object::new();
+        object.insert("a.b", 1);
+        object.insert("nested map", inner);
+
+        let collision = object
+            .try_sanitize_keys(Object::underscore_non_alphanumeric)
+            .unwrap_err();
+        assert_eq!(collision.key, "nested_map.inner_key");
+        assert!(object.contains_key("a.b"));
+
+        object.sanitize_keys(Object::underscore_non_alphanumeric);
+        assert_eq!(object.keys().collect::<Vec<_>>(), ["a_b", "nested_map"]);
+        let inner = object.get_as::<Object>("nested_map").unwrap();
+        assert_eq!(inner.get_as::<i32>("inner_key"), Some(&3));
+        assert_eq!(inner.len(), 1);
+    }
+
+    #[test]
+    fn test_object_macro_build() {
+        let include_debug = true;
+        let obj = object!({
+            a: @build |o| o.insert("x", 1),
+            b: @build |o| {
+                if include_debug {
+                    o.insert("debug", true);
+                }
+            },
+            c: 3,
+        });
+        assert_eq!(obj.len(), 3);
+        assert_eq!(obj.get_as::<Object>("a"), Some(&object!({ x: 1, })));
+        assert_eq!(obj.get_as::<Object>("b"), Some(&object!({ debug: true, })));
+        assert_eq!(obj.get_as::<i32>("c"), Some(&3));
+    }
+
+    #[test]
+    fn test_values_boxed_mut() {
+        let mut object = object!({
+            a: 1,
+            b: Foo { bar: 2 },
+            c: "three",
+        });
+        for (key, value) in object.values_boxed_mut() {
+            if key != "c" {
+                *value = Box::new(Null);
+            }
+        }
+        assert_eq!(object.get_as::<Null>("a"), Some(&Null));
+        assert_eq!(object.get_as::<Null>("b"), Some(&Null));
+        assert_eq!(object.get_as::<&str>("c"), Some(&"three"));
+    }
+
+    #[test]
+    fn test_dedup_values() {
+        let mut object = object!({
+            a: Foo { bar: 1 },
+            b: { x: 1, },
+            c: Foo { bar: 1 },
+            d: { x: 1, },
+            e: f64::NAN,
+            f: f64::NAN,
+            g: { x: 2, },
+        });
+        assert_eq!(object.dedup_values(), ["c", "d"]);
+        assert_eq!(object.keys().collect::<Vec<_>>(), ["a", "b", "e", "f", "g"]);
+        assert!(object.dedup_values().is_empty());
     }
 
     #[test]
-    fn works_with_mixed_object() {
-        let mixed = object!({
-            a: "xyz",
-            b: 69,
-            c: {
-                inner: "value",
-                another: {
-                    inner: 420,
-                    d: "tr4vvyr00lz",
+    fn test_object_path_macro() {
+        let object = object!({
+            a: {
+                b: {
+                    c: Foo { bar: 1 },
                 },
+                leaf: 2,
             },
-            d: Foo { bar: 123 },
         });
-        assert_eq!(mixed.get_as::<&str>("a"), Some(&"xyz"));
-        assert_eq!(mixed.get_as::<i32>("b"), Some(&69));
-        let c = mixed.get_as::<Object>("c");
-        assert!(c.is_some());
-        let c_inner = c.and_then(|v| v.get_as::<&str>("inner"));
-        assert_eq!(c_inner, Some(&"value"));
-        let c_inner_another = c.and_then(|v| v.get_as::<Object>("another"));
-        assert!(c_inner_another.is_some());
-        let c_inner_another_inner = c_inner_another.and_then(|v| v.get_as::<i32>("inner"));
-        assert_eq!(c_inner_another_inner, Some(&420));
-        let c_inner_another_d = c_inner_another.and_then(|v| v.get_as::<&str>("d"));
-        assert_eq!(c_inner_another_d, Some(&"tr4vvyr00lz"));
-        assert_eq!(mixed.get_as::<Foo>("d"), Some(&Foo { bar: 123 }));
+        assert_eq!(object_path!(object, a.b.c as Foo), Some(&Foo { bar: 1 }));
+        assert_eq!(object_path!(object, a.leaf as i32), Some(&2));
+        assert_eq!(object_path!(object, a.leaf.c as i32), None);
+        assert!(object_path!(object, a as Object).is_some());
+        let total = TotalObject::from(object);
+        assert!(object_path!(total, a.b as Object).is_some());
     }
 
     #[test]
-    fn works_like_a_map() {
-        let mut map = object!({});
-        assert!(map.get("foo").is_none());
-        map.insert("foo", "bar");
-        map.get_or_insert_as::<&str>("foo", "lol");
+    fn test_remove_path() {
+        let mut object = object!({
+            a: {
+                b: {
+                    c: 1,
+                },
+                d: 2,
+            },
+            e: 3,
+        });
+        assert!(!object.remove_path("e.f"));
+        assert!(!object.remove_path("a.x.c"));
+        assert!(!object.remove_path("a.b.c.d"));
+        assert!(object.remove_path_and_prune("a.b.c"));
+        assert_eq!(object_path!(object, a.d as i32), Some(&2));
+        assert!(object_path!(object, a.b as Object).is_none());
+        assert!(object.remove_path_and_prune("a.d"));
+        assert_eq!(object.keys().collect::<Vec<_>>(), ["e"]);
+        assert!(object.remove_path("e"));
+        assert!(object.is_empty());
+    }
+
+    #[test]
+    fn test_dyn_cmp_numeric() {
+        let one: Box<dyn AnyType> = Box::new(1i32);
+        assert_eq!((*one).dyn_cmp(&1i64), None);
+        assert_eq!((*one).dyn_cmp_numeric(&1i64), Some(Ordering::Equal));
+        assert_eq!((*one).dyn_cmp_numeric(&1.5f32), Some(Ordering::Less));
+        assert_eq!((*one).dyn_cmp_numeric(&u128::MAX), Some(Ordering::Less));
         assert_eq!(
-            map.get("foo").and_then(|v| v.downcast_ref::<&str>()),
-            Some(&"bar")
+            i64::MAX.dyn_cmp_numeric(&(i64::MAX - 1)),
+            Some(Ordering::Greater)
         );
-        map.get_or_insert_as::<&str>("bar", "baz");
         assert_eq!(
-            map.get("bar").and_then(|v| v.downcast_ref::<&str>()),
-            Some(&"baz")
+            u128::MAX.dyn_cmp_numeric(&(u128::MAX - 1)),
+            Some(Ordering::Greater)
         );
+        assert_eq!(u128::MAX.dyn_cmp_numeric(&-1i8), Some(Ordering::Greater));
+        assert_eq!(i128::MAX.dyn_cmp_numeric(&u128::MAX), Some(Ordering::Less));
+        assert_eq!((*one).dyn_cmp_numeric(&f64::NAN), None);
+        assert_eq!((*one).dyn_cmp_numeric(&"1"), None);
+        assert_eq!("a".dyn_cmp_numeric(&"b"), Some(Ordering::Less));
     }
 
     #[test]
-    fn test_equality() {
-        let a = object!({
-            foo: "bar",
-            baz: 123,
+    fn test_into_pairs() {
+        let object = object!({
+            c: Foo { bar: 3 },
+            a: 1,
+            b: "two",
         });
-        let b = object!({
-            foo: "bar",
-            baz: 123,
+        let pairs = object.into_pairs();
+        let keys: Vec<_> = pairs.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+        assert_eq!(pairs[2].1.downcast_ref::<Foo>(), Some(&Foo { bar: 3 }));
+        assert!(Object::new().into_pairs().is_empty());
+    }
+
+    #[test]
+    fn test_get_as_ctx() {
+        let object = object!({
+            foo: Foo { bar: 1 },
         });
-        assert_eq!(a, b);
+        assert_eq!(
+            object.get_as_ctx::<Foo>("foo", "section"),
+            Ok(&Foo { bar: 1 })
+        );
+        let missing = object.get_as_ctx::<Foo>("missing", "section").unwrap_err();
+        assert_eq!(
+            missing,
+            AccessError::Missing {
+                key: "missing".to_string(),
+                context: "section".to_string(),
+            }
+        );
+        assert_eq!(missing.to_string(), "section: key `missing` is missing");
+        assert!(matches!(
+            object.get_as_ctx::<i32>("foo", "section"),
+            Err(AccessError::WrongType {
+                found: "dynamic_object::tests::Foo",
+                ..
+            })
+        ));
+    }
 
-        let a = object!({
-            foo: "bar",
-            baz: 123,
+    #[test]
+    fn test_null_accessors() {
+        let object = object!({
+            null: Null,
+            unit: (),
+            zero: 0,
         });
-        let b = object!({
-            foo: "bar",
-            baz: 456,
+        assert!(object.is_null("null"));
+        assert!(object.is_null("unit"));
+        assert!(!object.is_null("zero"));
+        assert_eq!(object.get_optional_as::<Null>("null"), Some(&Null));
+        assert_eq!(object.get_optional_as::<i32>("null"), None);
+        assert_eq!(object.get_optional_as::<i32>("zero"), Some(&0));
+    }
+
+    #[test]
+    fn test_get_or_try_insert_with_as() {
+        let mut object = object!({
+            name: "name",
         });
-        assert_ne!(a, b);
+        let mut calls = 0;
+        let mut load = || {
+            calls += 1;
+            Ok::<_, String>(Foo { bar: 1 })
+        };
+        assert_eq!(
+            object.get_or_try_insert_with_as("foo", &mut load),
+            Ok(Some(&mut Foo { bar: 1 }))
+        );
+        assert_eq!(
+            object.get_or_try_insert_with_as("foo", &mut load),
+            Ok(Some(&mut Foo { bar: 1 }))
+        );
+        assert_eq!(
+            object.get_or_try_insert_with_as("name", &mut load),
+            Ok(None)
+        );
+        assert_eq!(calls, 1);
+        assert_eq!(
+            object.get_or_try_insert_with_as::<Foo, _>("bad", || Err("failed")),
+            Err("failed")
+        );
+        assert!(!object.contains_key("bad"));
+    }
 
-        let a = object!({
-            foo: "bar",
-            baz: 123,
+    #[test]
+    fn retain_top_n_breaks_ties_by_key() {
+        let mut object = object!({
+            d: 2.0,
+            c: 2.0,
+            b: f64::NAN,
+            a: 1.0,
+            e: 2.0f32,
         });
-        let b = object!({
-            foo: "bar",
-            baz: 123,
-            qux: "quux",
+        object.retain_top_n::<f64>(3);
+        let keys: Vec<_> = object.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["b", "c", "d", "e"]);
+
+        object.retain_top_n::<f64>(0);
+        assert_eq!(object, object!({ e: 2.0f32, }));
+
+        let mut object = object!({
+            a: -f64::NAN,
+            b: f64::INFINITY,
+            c: f64::NAN,
+            d: 0.0,
         });
-        assert_ne!(a, b);
+        object.retain_top_n::<f64>(3);
+        let keys: Vec<_> = object.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["a", "b", "c"]);
+    }
 
-        let a = object!({
-            foo: "bar",
-            baz: {
-                qux: "quux",
-            },
+    #[test]
+    fn get_two_as_reads_both_keys() {
+        let object = object!({
+            a: 1,
+            b: Foo { bar: 2 },
         });
-        let b = object!({
-            foo: "bar",
-            baz: {
-                qux: "quux",
+        assert_eq!(
+            object.get_two_as::<i32, Foo>("a", "b"),
+            (Some(&1), Some(&Foo { bar: 2 }))
+        );
+        assert_eq!(
+            object.get_two_as::<i32, i32>("a", "a"),
+            (Some(&1), Some(&1))
+        );
+        assert_eq!(object.get_two_as::<i32, i32>("missing", "b"), (None, None));
+    }
+
+    #[test]
+    fn prefix_all_keys_round_trips_with_strip_prefix() {
+        let mut object = object!({
+            a: 1,
+            nested: {
+                b: 2,
             },
         });
-        assert_eq!(a, b);
+        object.prefix_all_keys("ns.");
+        let keys: Vec<_> = object.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["ns.a", "ns.nested"]);
+        assert_eq!(
+            object.strip_prefix("ns"),
+            object!({ a: 1, nested: { b: 2, }, })
+        );
+        assert!(object.is_empty());
 
-        let a = object!({
-            foo: Foo { bar: 123 },
-        });
-        let b = object!({
-            foo: Foo { bar: 123 },
+        let mut object = object!({ a: 1 });
+        object.prefix_all_keys("");
+        object.prefix_all_keys(".");
+        assert_eq!(object, object!({ a: 1 }));
+    }
+
+    #[test]
+    fn get_by_type_id_matches_exact_type() {
+        let object = object!({
+            foo: Foo { bar: 1 },
+            nested: {},
         });
-        assert_eq!(a, b);
+        let foo = object.get_by_type_id("foo", TypeId::of::<Foo>()).unwrap();
+        assert_eq!(foo.downcast_ref::<Foo>(), Some(&Foo { bar: 1 }));
+        assert!(object
+            .get_by_type_id("nested", TypeId::of::<Object>())
+            .is_some());
+        assert!(object
+            .get_by_type_id("nested", TypeId::of::<Box<dyn AnyType>>())
+            .is_none());
+        assert!(object
+            .get_by_type_id("missing", TypeId::of::<Foo>())
+            .is_none());
     }
 
     #[test]
-    fn test_ordering() {
-        let a = object!({
-            foo: "bar",
-            baz: 123,
+    fn fold_as_skips_other_types() {
+        let object = object!({
+            a: Foo { bar: 2 },
+            b: 3,
+            c: Foo { bar: 5 },
         });
-        let b = object!({
-            foo: "bar",
-            baz: 123,
+        let product = object.fold_as::<Foo, _>(1, |acc, _, foo| acc * foo.bar);
+        assert_eq!(product, 10);
+        assert_eq!(object.fold_as::<f64, _>(0, |acc, _, _| acc + 1), 0);
+    }
+
+    #[test]
+    fn into_get_as_moves_value_out() {
+        let object = || object!({ foo: Foo { bar: 1 }, });
+        assert_eq!(object().into_get_as::<Foo>("foo"), Some(Foo { bar: 1 }));
+        assert_eq!(object().into_get_as::<i32>("foo"), None);
+        assert_eq!(object().into_get_as::<Foo>("missing"), None);
+    }
+
+    #[test]
+    fn alternate_debug_indents_nested_values() {
+        let object = object!({
+            a: {
+                b: vec![1],
+                c: Foo { bar: 2 },
+            },
         });
-        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+        let expected = r#"Object {
+    map: {
+        "a": Object {
+            map: {
+                "b": [
+                    1,
+                ],
+                "c": Foo {
+                    bar: 2,
+                },
+            },
+        },
+    },
+}"#;
+        assert_eq!(format!("{:#?}", object), expected);
+    }
 
-        let a = object!({
-            foo: "bar",
-            baz: 123,
+    #[test]
+    fn mut_or_replace_as_overwrites_other_types() {
+        let mut object = object!({
+            a: Foo { bar: 1 },
+            b: 2,
         });
-        let b = object!({
-            foo: "bar",
-            baz: 456,
+        object.mut_or_replace_as("a", Foo { bar: 0 }).bar += 1;
+        object.mut_or_replace_as("b", Foo { bar: 0 }).bar += 1;
+        object.mut_or_replace_as("c", Foo { bar: 0 }).bar += 1;
+        assert_eq!(
+            object,
+            object!({
+                a: Foo { bar: 2 },
+                b: Foo { bar: 1 },
+                c: Foo { bar: 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn index_mut_assigns_through_value() {
+        let mut object = object!({
+            foo: Foo { bar: 1 },
         });
-        assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
+        object["foo"].downcast_mut::<Foo>().unwrap().bar = 2;
+        assert_eq!(object["foo"].downcast_ref::<Foo>(), Some(&Foo { bar: 2 }));
+        assert!(object["foo"].downcast_mut::<i32>().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "key `missing` is not in the `Object`")]
+    fn index_panics_on_missing_key() {
+        let _ = &Object::new()["missing"];
+    }
 
+    #[test]
+    fn compares_key_sets_only() {
         let a = object!({
-            foo: "bar",
-            baz: 123,
+            a: 1,
+            b: Foo { bar: 1 },
         });
         let b = object!({
-            foo: "bar",
-            baz: 123,
-            qux: "quux",
+            b: 2,
+            c: 3,
         });
-        assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
+        assert!(a.same_keys(&a));
+        assert!(!a.same_keys(&b));
+        assert!(object!({ a: 2, b: 3, }).same_keys(&a));
+        assert_eq!(a.key_set_diff(&b), (vec!["c"], vec!["a"]));
+        assert_eq!(a.key_set_diff(&a), (vec![], vec![]));
+    }
 
-        let a = object!({
-            foo: "bar",
-            baz: {
-                qux: "quux",
+    #[test]
+    fn insert_object_reuses_or_replaces() {
+        let mut object = object!({
+            a: {
+                x: 1,
             },
+            b: 2,
         });
-        let b = object!({
-            foo: "bar",
-            baz: {
-                qux: "quux",
+        object.insert_object("a").insert("y", 2);
+        object.insert_object("b").insert("z", 3);
+        assert_eq!(
+            object,
+            object!({
+                a: {
+                    x: 1,
+                    y: 2,
+                },
+                b: {
+                    z: 3,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn navigate_walks_nested_objects() {
+        let object = object!({
+            a: {
+                b: Foo { bar: 1 },
             },
         });
-        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+        let mut dotted = Object::new();
+        dotted.insert("a.b", 2);
+        assert_eq!(
+            object.navigate(&["a", "b"]).unwrap().downcast_ref::<Foo>(),
+            Some(&Foo { bar: 1 })
+        );
+        assert_eq!(
+            object.navigate(&[]).unwrap().downcast_ref::<Object>(),
+            Some(&object)
+        );
+        assert_eq!(
+            dotted.navigate(&["a.b"]).unwrap().downcast_ref::<i32>(),
+            Some(&2)
+        );
+        assert!(object.navigate(&["a", "missing"]).is_none());
+    }
 
-        let a = object!({
-            foo: Foo { bar: 123 },
+    #[test]
+    fn truncates_from_either_end() {
+        let object = || object!({ a: 1, b: 2, c: 3, d: 4, });
+        let mut back = object();
+        assert_eq!(back.truncate(2), ["c", "d"]);
+        assert_eq!(back, object!({ a: 1, b: 2, }));
+        let mut front = object();
+        assert_eq!(front.truncate_front(1), ["a", "b", "c"]);
+        assert_eq!(front, object!({ d: 4, }));
+        assert_eq!(front.truncate_front(0), ["d"]);
+        assert!(front.is_empty());
+    }
+
+    #[test]
+    fn try_map_values_stops_at_first_error() {
+        let object = object!({
+            a: 1,
+            b: "two",
+            c: 3,
         });
-        let b = object!({
-            foo: Foo { bar: 123 },
+        let mut seen = Vec::new();
+        let result = object.try_map_values(|key, value| {
+            seen.push(key.to_string());
+            match value.downcast_ref::<i32>() {
+                Some(n) => Ok(Box::new(Foo { bar: *n }) as Box<dyn AnyType>),
+                None => Err(key.to_string()),
+            }
         });
-        assert_eq!(a.partial_cmp(&b), Some(Ordering::Equal));
+        assert_eq!(result, Err("b".to_string()));
+        assert_eq!(seen, ["a", "b"]);
+
+        let mut sealed = object!({ a: 1, });
+        sealed.seal();
+        let mapped = sealed.try_map_values(|_, v| Ok::<_, ()>(v)).unwrap();
+        assert!(mapped.is_sealed());
+        assert_eq!(mapped, object!({ a: 1, }));
     }
 
     #[test]
-    fn test_debug() {
+    fn insert_owned_converts_borrowed_strings() {
+        let mut object = Object::new();
+        object.insert_owned("a", "a");
+        object.insert_owned("b", Cow::<str>::Owned("b".to_string()));
+        object.insert_owned("c", Box::<str>::from("c"));
+        object.insert_owned("d", Foo { bar: 1 });
+        object.insert_owned("e", 'e');
+        for key in ["a", "b", "c"] {
+            assert_eq!(object.get_as::<String>(key).map(String::as_str), Some(key));
+        }
+        assert_eq!(object.get_as::<Foo>("d"), Some(&Foo { bar: 1 }));
+        assert_eq!(object.get_as::<char>("e"), Some(&'e'));
+
+        object.seal();
+        object.insert_owned("f", "f");
+        assert!(!object.contains_key("f"));
+    }
+
+    #[test]
+    fn object_macro_accepts_missing_trailing_comma_and_shorthand() {
+        let foo = Foo { bar: 1 };
+        let flag = true;
         let obj = object!({
-            foo: "bar",
-            baz: 123,
+            foo,
+            a: 1,
+            nested: { b: 2 },
+            built: @build |o| o.insert("c", 3),
+            maybe: 4 if flag
         });
         assert_eq!(
-            format!("{:?}", obj),
-            r#"Object { map: {"baz": 123, "foo": "bar"} }"#
+            obj,
+            object!({
+                a: 1,
+                built: {
+                    c: 3,
+                },
+                foo: Foo { bar: 1 },
+                maybe: 4,
+                nested: {
+                    b: 2,
+                },
+            })
         );
+        assert_eq!(object!({ a: 1 }), object!({ a: 1, }));
+        assert_eq!(object!({ a: { x: 1 } if !flag }), Object::new());
     }
 
     #[test]
-    fn preserves_order() {
-        let obj = object!({
-            foo: "bar",
-            baz: 123,
-        });
-        let keys: Vec<_> = obj.keys().cloned().collect();
-        assert_eq!(keys, vec!["baz", "foo"]);
+    fn is_and_is_object_check_the_value_type() {
+        let obj = object!({ foo: Foo { bar: 1 }, nested: { a: 1 } });
+        let foo: &dyn AnyType = &**obj.get("foo").unwrap();
+        assert!(foo.is::<Foo>());
+        assert!(!foo.is::<Box<dyn AnyType>>());
+        assert!(!foo.is_object());
+        assert!(obj.get("nested").is_some_and(|v| v.is_object()));
+        assert!(!obj.get("nested").is_some_and(|v| v.is::<Foo>()));
+    }
 
-        let obj = object!({
-            foo: "bar",
-            baz: 123,
-            qux: "quux",
-        });
-        let keys: Vec<_> = obj.keys().cloned().collect();
-        assert_eq!(keys, vec!["baz", "foo", "qux"]);
+    #[test]
+    fn test_from_boxed_map_keeps_values() {
+        let mut object = object!({ a: 1, b: { c: "x" } });
+        let rebuilt = Object::from_boxed_map(std::mem::take(&mut *object));
+        assert_eq!(rebuilt.get_as::<i32>("a"), Some(&1));
+        assert!(rebuilt.deep_eq(&object!({ a: 1, b: { c: "x" } })));
 
-        let obj = object!({
-            foo: "bar",
-            baz: {
-                qux: "quux",
-            },
-        });
+        let mut map = BTreeMap::new();
+        map.insert("a".to_string(), Box::new(1) as Box<dyn AnyType>);
+        assert_eq!(Object::from(map).get_as::<i32>("a"), None);
+    }
 
-        let keys: Vec<_> = obj.keys().cloned().collect();
-        let entries = obj.iter().collect::<Vec<_>>();
+    #[test]
+    fn sealed_objects_reject_new_keys() {
+        let sealed = || {
+            let mut object = object!({ a: 1, nested: { b: 2 } });
+            object.seal();
+            object
+        };
+        let unchanged = || object!({ a: 1, nested: { b: 2 } });
+        let rejected = |key: &str| InsertError::Sealed {
+            key: key.to_string(),
+        };
 
-        for (i, (k, v)) in obj.iter().enumerate() {
-            let key = keys[i].clone();
-            assert_eq!(k, &key);
-            assert_eq!(entries[i].0, &key);
-            assert_eq!(entries[i].1, v);
-        }
+        let mut obj = sealed();
+        assert_eq!(obj.get_or_insert_as("x", 1), None);
+        assert_eq!(obj.try_get_or_insert_as("x", 1).unwrap_err(), rejected("x"));
+        assert_eq!(obj.entry_ref_as("x", || 1).unwrap_err(), rejected("x"));
+        assert_eq!(obj.insert_checked("x", 1), Err(rejected("x")));
+        assert_eq!(
+            obj.get_or_try_insert_with_as("x", || Ok::<_, ()>(1)),
+            Ok(None)
+        );
+        assert_eq!(
+            obj.path_entry_as("x.y", || 1),
+            Err(PathError::Sealed {
+                path: "x".to_string(),
+            })
+        );
+        assert_eq!(
+            obj.path_entry_as("a", || 0).map(|a| *a),
+            Ok(1),
+            "existing keys are still reachable"
+        );
+        assert_eq!(
+            obj.insert_all([("a", 3), ("x", 4)]),
+            [
+                ("a".to_string(), InsertOutcome::Overwritten),
+                ("x".to_string(), InsertOutcome::Rejected),
+            ]
+        );
+        obj.insert("a", 1);
+        assert_eq!(obj, unchanged());
+
+        obj.merge_with(object!({ a: 1, x: 2 }), |_, _, theirs| theirs);
+        obj.merge_preferring_non_null(object!({ x: 2 }));
+        obj.deep_merge(object!({ x: 2 }));
+        obj.apply_patch(ObjectPatch::from(vec![PatchOp::Set {
+            path: vec!["x".to_string(), "y".to_string()],
+            value: Box::new(1),
+        }]));
+        assert_eq!(obj, unchanged());
+
+        let mut checkpointed = Checkpointed::from(sealed());
+        checkpointed.insert("x", 1);
+        assert_eq!(checkpointed.changes(), 0);
+        let mut observable = ObservableObject::from(sealed());
+        observable.observe(|_| panic!("no key may be added"));
+        observable.insert("x", 1);
+        assert_eq!(observable.into_inner(), unchanged());
+    }
+
+    #[test]
+    fn seal_exceptions_and_fallible_insert() {
+        let mut obj = object!({ a: 1 });
+        obj.seal();
+        let mut calls = 0;
+        let inserted = obj.get_or_try_insert_with_as("x", || {
+            calls += 1;
+            Ok::<_, ()>(1)
+        });
+        assert_eq!(inserted, Ok(None));
+        assert_eq!(calls, 0);
+        assert!(!obj.contains_key("x"));
+
+        *obj.mut_or_replace_as("count", 0) += 1;
+        obj.insert_object("nested").insert("b", 2);
+        obj.typed_entry("entry").or_insert(true).unwrap();
+        assert_eq!(
+            obj,
+            object!({ a: 1, count: 1, entry: true, nested: { b: 2 } })
+        );
     }
 }
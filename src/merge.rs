@@ -0,0 +1,284 @@
+//! Recursive merging of objects and arrays.
+
+use crate::{AnyType, Array, Object};
+
+/// How [`Object::deep_merge_with_options`] combines two arrays stored under the same key.
+///
+/// Arrays are values of type `Vec<Box<dyn AnyType>>`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The incoming array replaces the existing one, as in JSON Merge Patch.
+    #[default]
+    Replace,
+    /// The incoming elements are appended to the existing ones.
+    Concat,
+    /// Elements at the same index are merged with the same options, and the extra elements of
+    /// the longer array are kept.
+    ElementWise,
+}
+
+/// How [`Object::deep_merge_with_options`] combines two objects stored under the same key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ObjectMerge {
+    /// The objects are merged recursively.
+    #[default]
+    Deep,
+    /// The incoming object replaces the existing one.
+    Replace,
+}
+
+/// Options for [`Object::deep_merge_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// How arrays are combined. Defaults to [`ArrayMerge::Replace`].
+    pub arrays: ArrayMerge,
+    /// How nested objects are combined. Defaults to [`ObjectMerge::Deep`].
+    pub objects: ObjectMerge,
+}
+
+impl Object {
+    /// Merges `other` into the `Object` recursively, using the default [`MergeOptions`].
+    ///
+    /// Nested objects present on both sides are merged, and any other value from `other`
+    /// replaces the existing one, arrays included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let mut config = object!({
+    ///     server: {
+    ///         host: "localhost",
+    ///         port: 80,
+    ///     },
+    /// });
+    /// config.deep_merge(object!({
+    ///     server: {
+    ///         port: 8080,
+    ///     },
+    /// }));
+    /// let server = config.get_as::<Object>("server").unwrap();
+    /// assert_eq!(server.get_as::<&str>("host"), Some(&"localhost"));
+    /// assert_eq!(server.get_as::<i32>("port"), Some(&8080));
+    /// ```
+    pub fn deep_merge(&mut self, other: Object) {
+        self.deep_merge_with_options(other, MergeOptions::default());
+    }
+
+    /// Merges `other` into the `Object` recursively, combining arrays and nested objects
+    /// according to `options`.
+    ///
+    /// Values present on only one side are kept as-is, and values of any other type from `other`
+    /// replace the existing ones. Values are moved, not cloned. Keys only in `other` are skipped
+    /// if the `Object` is [sealed](Object::seal), and likewise for nested objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, AnyType, ArrayMerge, MergeOptions};
+    /// let mut a = object!({
+    ///     tags: vec![Box::new("a") as Box<dyn AnyType>],
+    /// });
+    /// let b = object!({
+    ///     tags: vec![Box::new("b") as Box<dyn AnyType>],
+    /// });
+    /// let options = MergeOptions {
+    ///     arrays: ArrayMerge::Concat,
+    ///     ..MergeOptions::default()
+    /// };
+    /// a.deep_merge_with_options(b, options);
+    /// assert_eq!(a.get_as_slice::<Box<dyn AnyType>>("tags").unwrap().len(), 2);
+    /// ```
+    pub fn deep_merge_with_options(&mut self, other: Object, options: MergeOptions) {
+        for (key, theirs) in other.map {
+            let value = match self.map.remove(&key) {
+                Some(ours) => merge_values(ours, theirs, options),
+                None if self.sealed => continue,
+                None => theirs,
+            };
+            self.map.insert(key, value);
+        }
+    }
+
+    /// Folds `objects` into a single `Object` from left to right, with the values of later
+    /// objects replacing those of earlier ones under the same key.
+    ///
+    /// The merge is shallow: a nested object from a later object replaces the earlier one as a
+    /// whole. Use [`Object::deep_merge_all`] to merge nested objects key by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let defaults = object!({ host: "localhost", port: 80, });
+    /// let file = object!({ port: 8080, });
+    /// let env = object!({ host: "example.com", });
+    /// let config = Object::merge_all([defaults, file, env]);
+    /// assert_eq!(config, object!({ host: "example.com", port: 8080, }));
+    /// ```
+    pub fn merge_all(objects: impl IntoIterator<Item = Object>) -> Object {
+        objects
+            .into_iter()
+            .fold(Object::new(), |mut merged, object| {
+                merged.map.extend(object.map);
+                merged
+            })
+    }
+
+    /// Folds `objects` into a single `Object` from left to right with [`Object::deep_merge`], so
+    /// nested objects are merged key by key and later values win.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, Object};
+    /// let defaults = object!({ server: { host: "localhost", port: 80, }, });
+    /// let file = object!({ server: { port: 8080, }, });
+    /// let env = object!({ debug: true, });
+    /// let config = Object::deep_merge_all([defaults, file, env]);
+    /// assert_eq!(
+    ///     config,
+    ///     object!({
+    ///         debug: true,
+    ///         server: {
+    ///             host: "localhost",
+    ///             port: 8080,
+    ///         },
+    ///     })
+    /// );
+    /// ```
+    pub fn deep_merge_all(objects: impl IntoIterator<Item = Object>) -> Object {
+        objects
+            .into_iter()
+            .fold(Object::new(), |mut merged, object| {
+                merged.deep_merge(object);
+                merged
+            })
+    }
+}
+
+fn merge_values(
+    ours: Box<dyn AnyType>,
+    theirs: Box<dyn AnyType>,
+    options: MergeOptions,
+) -> Box<dyn AnyType> {
+    let both = |is: fn(&dyn AnyType) -> bool| is(&*ours) && is(&*theirs);
+    if options.objects == ObjectMerge::Deep && both(|v| v.as_any().is::<Object>()) {
+        let mut ours = downcast::<Object>(ours);
+        ours.deep_merge_with_options(*downcast::<Object>(theirs), options);
+        return ours;
+    }
+    if options.arrays != ArrayMerge::Replace && both(|v| v.as_any().is::<Array>()) {
+        let mut ours = downcast::<Array>(ours);
+        let mut theirs = downcast::<Array>(theirs).into_iter();
+        if options.arrays == ArrayMerge::ElementWise {
+            for (ours, theirs) in ours.iter_mut().zip(theirs.by_ref()) {
+                let existing = std::mem::replace(ours, Box::new(()));
+                *ours = merge_values(existing, theirs, options);
+            }
+        }
+        ours.extend(theirs);
+        return ours;
+    }
+    theirs
+}
+
+fn downcast<T: AnyType>(value: Box<dyn AnyType>) -> Box<T> {
+    AnyType::into_any(value)
+        .downcast::<T>()
+        .expect("the type was checked by the caller")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    fn array(values: [i32; 2]) -> Array {
+        values
+            .into_iter()
+            .map(|v| Box::new(v) as Box<dyn AnyType>)
+            .collect()
+    }
+
+    fn merged(options: MergeOptions) -> Object {
+        let mut ours = object!({
+            list: array([1, 2]),
+            nested: {
+                a: 1,
+                b: 2,
+            },
+            objects: vec![Box::new(object!({ x: 1, })) as Box<dyn AnyType>],
+        });
+        let theirs = object!({
+            list: array([3, 4]),
+            nested: {
+                b: 3,
+            },
+            objects: vec![
+                Box::new(object!({ y: 2, })) as Box<dyn AnyType>,
+                Box::new(5),
+            ],
+        });
+        ours.deep_merge_with_options(theirs, options);
+        ours
+    }
+
+    fn ints(object: &Object, key: &str) -> Vec<i32> {
+        object
+            .get_as_slice::<Box<dyn AnyType>>(key)
+            .unwrap()
+            .iter()
+            .map(|v| *v.downcast_ref::<i32>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn combines_arrays_and_objects_per_options() {
+        let object = merged(MergeOptions::default());
+        assert_eq!(ints(&object, "list"), [3, 4]);
+        let nested = object.get_as::<Object>("nested").unwrap();
+        assert_eq!(nested.get_as::<i32>("a"), Some(&1));
+        assert_eq!(nested.get_as::<i32>("b"), Some(&3));
+
+        let object = merged(MergeOptions {
+            arrays: ArrayMerge::Concat,
+            objects: ObjectMerge::Replace,
+        });
+        assert_eq!(ints(&object, "list"), [1, 2, 3, 4]);
+        let nested = object.get_as::<Object>("nested").unwrap();
+        assert_eq!(nested.keys().collect::<Vec<_>>(), ["b"]);
+
+        let object = merged(MergeOptions {
+            arrays: ArrayMerge::ElementWise,
+            objects: ObjectMerge::Deep,
+        });
+        assert_eq!(ints(&object, "list"), [3, 4]);
+        let objects = object.get_as_slice::<Box<dyn AnyType>>("objects").unwrap();
+        assert_eq!(objects.len(), 2);
+        let first = objects[0].downcast_ref::<Object>().unwrap();
+        assert_eq!(first.keys().collect::<Vec<_>>(), ["x", "y"]);
+        assert_eq!(objects[1].downcast_ref::<i32>(), Some(&5));
+    }
+
+    #[test]
+    fn merge_all_folds_left_to_right() {
+        let layers = || {
+            [
+                object!({ a: 1, nested: { x: 1, y: 1, }, }),
+                object!({ a: 2, nested: { y: 2, }, }),
+                object!({ b: 3, }),
+            ]
+        };
+        assert_eq!(
+            Object::merge_all(layers()),
+            object!({ a: 2, b: 3, nested: { y: 2, }, })
+        );
+        assert_eq!(
+            Object::deep_merge_all(layers()),
+            object!({ a: 2, b: 3, nested: { x: 1, y: 2, }, })
+        );
+        assert_eq!(Object::merge_all([]), Object::new());
+    }
+}
@@ -0,0 +1,227 @@
+//! Type-agnostic access to numeric values.
+
+use std::cmp::Ordering;
+
+use crate::{AnyType, Array, Object};
+
+macro_rules! downcast_any {
+    ($value:expr, $convert:expr, $($ty:ty),*) => {
+        $(
+            if let Some(n) = $value.downcast_ref::<$ty>() {
+                return $convert(*n);
+            }
+        )*
+    };
+}
+
+/// Widens a value of any primitive integer type to `i128`.
+pub(crate) fn to_i128(value: &dyn AnyType) -> Option<i128> {
+    if let Some(n) = value.downcast_ref::<i128>() {
+        return Some(*n);
+    }
+    downcast_any!(
+        value,
+        |n| Some(i128::from(n)),
+        i8,
+        i16,
+        i32,
+        i64,
+        u8,
+        u16,
+        u32,
+        u64
+    );
+    downcast_any!(value, |n| i128::try_from(n).ok(), isize, usize, u128);
+    None
+}
+
+/// Compares two values of any primitive integer types exactly, or returns `None` if either isn't
+/// an integer.
+pub(crate) fn cmp_integers(a: &dyn AnyType, b: &dyn AnyType) -> Option<Ordering> {
+    // Only a `u128` above `i128::MAX` doesn't widen to `i128`, and it is greater than any value
+    // that does.
+    let wide = |value: &dyn AnyType| match value.downcast_ref::<u128>() {
+        Some(&n) if i128::try_from(n).is_err() => Some(Err(n)),
+        _ => to_i128(value).map(Ok),
+    };
+    Some(match (wide(a)?, wide(b)?) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Err(a), Err(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+    })
+}
+
+/// Converts a value of any primitive numeric type to `f64`, possibly losing precision.
+pub(crate) fn to_f64(value: &dyn AnyType) -> Option<f64> {
+    if let Some(n) = value.downcast_ref::<f64>() {
+        return Some(*n);
+    }
+    if let Some(n) = value.downcast_ref::<f32>() {
+        return Some(f64::from(*n));
+    }
+    if let Some(n) = value.downcast_ref::<u128>() {
+        return Some(*n as f64);
+    }
+    to_i128(value).map(|n| n as f64)
+}
+
+impl Object {
+    /// Returns the value corresponding to the key as an `f64` if it is of any primitive numeric
+    /// type, or `None` if it isn't.
+    ///
+    /// This saves guessing whether a literal was stored as `i32`, `i64` or `f64`. Integers with a
+    /// magnitude above 2<sup>53</sup> can't all be represented by an `f64` and are rounded; use
+    /// [`Object::get_integer`] when exact integer values matter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     int: 2,
+    ///     byte: 3u8,
+    ///     float: 1.5f32,
+    ///     text: "4",
+    /// });
+    /// assert_eq!(object.get_number("int"), Some(2.0));
+    /// assert_eq!(object.get_number("byte"), Some(3.0));
+    /// assert_eq!(object.get_number("float"), Some(1.5));
+    /// assert_eq!(object.get_number("text"), None);
+    /// ```
+    pub fn get_number(&self, key: &str) -> Option<f64> {
+        to_f64(&**self.map.get(key)?)
+    }
+
+    /// Returns the value corresponding to the key as an `i128` if it is of any primitive integer
+    /// type, or `None` if it isn't.
+    ///
+    /// Floats are never converted. A `u128` above `i128::MAX` also returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let object = object!({
+    ///     big: u64::MAX,
+    ///     small: -1i8,
+    ///     float: 1.0,
+    /// });
+    /// assert_eq!(object.get_integer("big"), Some(u64::MAX as i128));
+    /// assert_eq!(object.get_integer("small"), Some(-1));
+    /// assert_eq!(object.get_integer("float"), None);
+    /// ```
+    pub fn get_integer(&self, key: &str) -> Option<i128> {
+        to_i128(&**self.map.get(key)?)
+    }
+
+    /// Returns `true` if both objects have the same keys and equal values, treating numbers of
+    /// different primitive types as equal when they have the same value.
+    ///
+    /// Any pair of the integer and float primitives (`i8` through `i128`, `isize`, `u8` through
+    /// `u128`, `usize`, `f32` and `f64`) is compared with [`AnyType::dyn_cmp_numeric`]: two
+    /// integers are compared exactly, while a float is compared with an integer after converting
+    /// both to `f64`, so `5i32`, `5u64` and `5.0f32` are all equal, but `NaN` equals nothing.
+    /// Nested objects and arrays stored as `Vec<Box<dyn AnyType>>` are compared recursively by
+    /// the same rules. Every other value is only equal to a value of the same type that compares
+    /// equal, so `true` never equals `1`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let a = object!({
+    ///     id: 5i32,
+    ///     ratio: 1.0f64,
+    ///     name: "a",
+    ///     nested: {
+    ///         count: 2u8,
+    ///     },
+    /// });
+    /// let b = object!({
+    ///     id: 5i64,
+    ///     ratio: 1u16,
+    ///     name: "a",
+    ///     nested: {
+    ///         count: 2usize,
+    ///     },
+    /// });
+    /// assert!(a.value_eq_numeric(&b));
+    /// assert!(!a.deep_eq(&b));
+    /// assert!(!object!({ flag: true, }).value_eq_numeric(&object!({ flag: 1, })));
+    /// ```
+    pub fn value_eq_numeric(&self, other: &Object) -> bool {
+        self.map.len() == other.map.len()
+            && self.map.iter().all(|(key, value)| {
+                other
+                    .map
+                    .get(key)
+                    .is_some_and(|other| values_eq_numeric(&**value, &**other))
+            })
+    }
+}
+
+fn values_eq_numeric(a: &dyn AnyType, b: &dyn AnyType) -> bool {
+    if let (Some(a), Some(b)) = (a.downcast_ref::<Object>(), b.downcast_ref::<Object>()) {
+        return a.value_eq_numeric(b);
+    }
+    if let (Some(a), Some(b)) = (a.downcast_ref::<Array>(), b.downcast_ref::<Array>()) {
+        return a.len() == b.len() && a.iter().zip(b).all(|(a, b)| values_eq_numeric(&**a, &**b));
+    }
+    a.dyn_cmp_numeric(b) == Some(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{object, AnyType, Array};
+
+    #[test]
+    fn widens_every_numeric_type() {
+        let object = object!({
+            a: 1i8,
+            b: 2i16,
+            c: 3i64,
+            d: 4usize,
+            e: u128::MAX,
+            f: 2.5f64,
+            g: (1i64 << 53) + 1,
+            h: true,
+        });
+        assert_eq!(object.get_integer("a"), Some(1));
+        assert_eq!(object.get_integer("b"), Some(2));
+        assert_eq!(object.get_integer("c"), Some(3));
+        assert_eq!(object.get_integer("d"), Some(4));
+        assert_eq!(object.get_integer("e"), None);
+        assert_eq!(object.get_number("e"), Some(u128::MAX as f64));
+        assert_eq!(object.get_number("f"), Some(2.5));
+        assert_eq!(object.get_integer("g"), Some((1 << 53) + 1));
+        assert_eq!(object.get_number("g"), Some((1u64 << 53) as f64));
+        assert_eq!(object.get_number("h"), None);
+        assert_eq!(object.get_number("missing"), None);
+    }
+
+    #[test]
+    fn value_eq_numeric_crosses_numeric_types_only() {
+        let list = |values: [Box<dyn AnyType>; 2]| Array::from(values);
+        let a = object!({
+            big: u64::MAX,
+            list: list([Box::new(1i8), Box::new(object!({ x: 0.5f32, }))]),
+        });
+        let b = object!({
+            big: u64::MAX as i128,
+            list: list([Box::new(1.0f64), Box::new(object!({ x: 0.5f64, }))]),
+        });
+        assert!(a.value_eq_numeric(&b));
+        assert!(!a.value_eq_numeric(&object!({ big: u64::MAX, })));
+        assert!(!object!({ a: f64::NAN, }).value_eq_numeric(&object!({ a: f64::NAN, })));
+        assert!(!object!({ a: "1", }).value_eq_numeric(&object!({ a: 1, })));
+        assert!(!object!({ a: 1, }).value_eq_numeric(&object!({ b: 1, })));
+    }
+
+    #[test]
+    fn value_eq_numeric_distinguishes_large_u128() {
+        let max = object!({ a: u128::MAX });
+        assert!(!max.value_eq_numeric(&object!({ a: u128::MAX - 1 })));
+        assert!(max.value_eq_numeric(&object!({ a: u128::MAX })));
+    }
+}
@@ -0,0 +1,219 @@
+//! An `Object` wrapper that notifies observers of changes.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::{AnyType, Object};
+
+/// A change made to an `ObservableObject`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeEvent {
+    /// A value was inserted under a new key.
+    Inserted {
+        /// The inserted key.
+        key: String,
+    },
+    /// A key was removed.
+    Removed {
+        /// The removed key.
+        key: String,
+    },
+    /// The value of an existing key was replaced.
+    Replaced {
+        /// The replaced key.
+        key: String,
+    },
+}
+
+type Observer = Box<dyn FnMut(&ChangeEvent)>;
+
+/// An `Object` that calls registered observers after every change.
+///
+/// Reads go through `Deref` to the wrapped `Object`. Mutation is only possible through the
+/// methods of `ObservableObject`, so no change goes unnoticed.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::RefCell, rc::Rc};
+/// use dynamic_object::{ChangeEvent, ObservableObject};
+///
+/// let events = Rc::new(RefCell::new(Vec::new()));
+/// let mut object = ObservableObject::new();
+/// let log = Rc::clone(&events);
+/// object.observe(move |event| log.borrow_mut().push(event.clone()));
+///
+/// object.insert("key", "value");
+/// assert_eq!(object.get_as::<&str>("key"), Some(&"value"));
+/// assert_eq!(
+///     events.borrow().as_slice(),
+///     [ChangeEvent::Inserted { key: "key".to_string() }]
+/// );
+/// ```
+#[derive(Default)]
+pub struct ObservableObject {
+    object: Object,
+    observers: Vec<Observer>,
+}
+
+impl ObservableObject {
+    /// Creates a new, empty `ObservableObject`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::ObservableObject;
+    /// let object = ObservableObject::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a callback that is called after every change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::ObservableObject;
+    /// let mut object = ObservableObject::new();
+    /// object.observe(|event| println!("{:?}", event));
+    /// ```
+    pub fn observe(&mut self, observer: impl FnMut(&ChangeEvent) + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Inserts a key-value pair, then notifies the observers.
+    ///
+    /// Fires `ChangeEvent::Replaced` if the key already existed, and `ChangeEvent::Inserted` otherwise.
+    /// As with [`Object::insert`], a new key is ignored if the wrapped `Object` is
+    /// [sealed](Object::seal), and no event is fired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::ObservableObject;
+    /// let mut object = ObservableObject::new();
+    /// object.insert("key", "value");
+    /// ```
+    pub fn insert<K: Into<String>, V: AnyType>(&mut self, key: K, value: V) {
+        let key = key.into();
+        if self.object.sealed && !self.object.map.contains_key(&key) {
+            return;
+        }
+        let event = match self.object.map.insert(key.clone(), Box::new(value)) {
+            Some(_) => ChangeEvent::Replaced { key },
+            None => ChangeEvent::Inserted { key },
+        };
+        self.notify(&event);
+    }
+
+    /// Removes a key, returning its value if it existed, then notifies the observers.
+    ///
+    /// Observers are only notified if the key was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::ObservableObject;
+    /// let mut object = ObservableObject::new();
+    /// object.insert("key", "value");
+    /// let value = object.remove("key");
+    /// assert!(value.is_some());
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<Box<dyn AnyType>> {
+        let value = self.object.map.remove(key)?;
+        self.notify(&ChangeEvent::Removed {
+            key: key.to_string(),
+        });
+        Some(value)
+    }
+
+    /// Consumes the `ObservableObject`, returning the wrapped `Object`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::ObservableObject;
+    /// let mut object = ObservableObject::new();
+    /// object.insert("key", "value");
+    /// let object = object.into_inner();
+    /// assert_eq!(object.get_as::<&str>("key"), Some(&"value"));
+    /// ```
+    pub fn into_inner(self) -> Object {
+        self.object
+    }
+
+    fn notify(&mut self, event: &ChangeEvent) {
+        for observer in &mut self.observers {
+            observer(event);
+        }
+    }
+}
+
+impl From<Object> for ObservableObject {
+    fn from(object: Object) -> Self {
+        Self {
+            object,
+            observers: Vec::new(),
+        }
+    }
+}
+
+impl Deref for ObservableObject {
+    type Target = Object;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl Debug for ObservableObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservableObject")
+            .field("object", &self.object)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn fires_events_after_mutating() {
+        let mut object = ObservableObject::from(object!({
+            existing: 1,
+        }));
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&events);
+        object.observe(move |event| log.borrow_mut().push(event.clone()));
+
+        object.insert("new", 2);
+        object.insert("existing", 3);
+        assert!(object.remove("new").is_some());
+        assert!(object.remove("missing").is_none());
+
+        assert_eq!(object.get_as::<i32>("existing"), Some(&3));
+        assert_eq!(
+            events.borrow().as_slice(),
+            [
+                ChangeEvent::Inserted {
+                    key: "new".to_string()
+                },
+                ChangeEvent::Replaced {
+                    key: "existing".to_string()
+                },
+                ChangeEvent::Removed {
+                    key: "new".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Patches describing how to turn one `Object` into another.
+
+use crate::{values_deep_eq, AnyType, Object};
+
+/// A single operation of an [`ObjectPatch`].
+///
+/// Paths list the keys leading to the value, from the outermost `Object` inwards, so unlike
+/// dotted paths they can address keys containing a `.`. An operation with an empty path does
+/// nothing.
+#[derive(Debug, PartialEq)]
+pub enum PatchOp {
+    /// Stores `value` at `path`, creating intermediate objects as needed.
+    Set {
+        /// The keys leading to the value.
+        path: Vec<String>,
+        /// The value to store.
+        value: Box<dyn AnyType>,
+    },
+    /// Removes the value at `path`, if any.
+    Remove {
+        /// The keys leading to the value.
+        path: Vec<String>,
+    },
+}
+
+/// A list of operations turning one `Object` into another, created by [`Object::make_patch`].
+///
+/// With the `serde` feature, a patch can be serialized and deserialized; each operation is written
+/// as a map with an `op` (`"set"` or `"remove"`), a `path` as a list of keys and, for `set`, a
+/// tagged `value` in the same format as `Object`.
+#[derive(Debug, Default, PartialEq)]
+pub struct ObjectPatch {
+    pub(crate) ops: Vec<PatchOp>,
+}
+
+impl ObjectPatch {
+    /// Creates an empty patch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::ObjectPatch;
+    /// assert!(ObjectPatch::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the operations of the patch, in the order they are applied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, PatchOp};
+    /// let patch = object!({ a: 1, }).make_patch(object!({}));
+    /// assert_eq!(patch.ops(), [PatchOp::Remove { path: vec!["a".to_string()] }]);
+    /// ```
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    /// Returns `true` if the patch has no operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let patch = object!({ a: 1, }).make_patch(object!({ a: 1, }));
+    /// assert!(patch.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl From<Vec<PatchOp>> for ObjectPatch {
+    fn from(ops: Vec<PatchOp>) -> Self {
+        Self { ops }
+    }
+}
+
+impl Object {
+    /// Returns the patch that turns `self` into `target`.
+    ///
+    /// Nested objects are compared key by key, so only the leaves that differ end up in the
+    /// patch. The removals of each object come before its sets. Values can't be cloned, so
+    /// `target` is consumed and its differing values are moved into the patch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, PatchOp};
+    /// let mut obj = object!({
+    ///     name: "app",
+    ///     server: {
+    ///         port: 80,
+    ///         host: "localhost",
+    ///     },
+    /// });
+    /// let target = object!({
+    ///     name: "app",
+    ///     server: {
+    ///         port: 8080,
+    ///     },
+    /// });
+    /// let patch = obj.make_patch(target);
+    /// assert_eq!(patch.ops().len(), 2);
+    /// let path = vec!["server".to_string(), "host".to_string()];
+    /// assert_eq!(patch.ops()[0], PatchOp::Remove { path });
+    ///
+    /// obj.apply_patch(patch);
+    /// assert_eq!(obj, object!({ name: "app", server: { port: 8080, }, }));
+    /// ```
+    pub fn make_patch(&self, target: Object) -> ObjectPatch {
+        let mut ops = Vec::new();
+        diff(self, target, &[], &mut ops);
+        ObjectPatch { ops }
+    }
+
+    /// Applies the operations of `patch` in order.
+    ///
+    /// A `set` whose path runs through a value that is not an `Object` replaces it with a new
+    /// `Object`. A `set` that would add a key to a [sealed](Object::seal) `Object` is skipped,
+    /// and a `remove` of an absent path does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, ObjectPatch, PatchOp};
+    /// let mut obj = object!({ a: 1, });
+    /// obj.apply_patch(ObjectPatch::from(vec![
+    ///     PatchOp::Set { path: vec!["b".to_string(), "c".to_string()], value: Box::new(2) },
+    ///     PatchOp::Remove { path: vec!["a".to_string()] },
+    /// ]));
+    /// assert_eq!(obj, object!({ b: { c: 2, }, }));
+    /// ```
+    pub fn apply_patch(&mut self, patch: ObjectPatch) {
+        for op in patch.ops {
+            match op {
+                PatchOp::Set { path, value } => set_path(self, &path, value),
+                PatchOp::Remove { path } => remove_path(self, &path),
+            }
+        }
+    }
+}
+
+fn diff(current: &Object, mut target: Object, prefix: &[String], ops: &mut Vec<PatchOp>) {
+    let join = |key: &str| {
+        let mut path = prefix.to_vec();
+        path.push(key.to_string());
+        path
+    };
+    for key in current.map.keys() {
+        if !target.map.contains_key(key) {
+            ops.push(PatchOp::Remove { path: join(key) });
+        }
+    }
+    for (key, value) in std::mem::take(&mut target.map) {
+        let path = join(&key);
+        match current.map.get(&key) {
+            Some(old) if values_deep_eq(&**old, &*value) => {}
+            Some(old) if old.as_object().is_some() && (*value).as_object().is_some() => {
+                let nested = AnyType::into_any(value)
+                    .downcast::<Object>()
+                    .expect("value is an Object");
+                diff(old.as_object().unwrap(), *nested, &path, ops);
+            }
+            _ => ops.push(PatchOp::Set { path, value }),
+        }
+    }
+}
+
+fn set_path(object: &mut Object, path: &[String], value: Box<dyn AnyType>) {
+    let Some((leaf, parents)) = path.split_last() else {
+        return;
+    };
+    let mut object = object;
+    for segment in parents {
+        if object.sealed && !object.map.contains_key(segment) {
+            return;
+        }
+        let slot = object
+            .map
+            .entry(segment.clone())
+            .or_insert_with(|| Box::new(Object::new()));
+        if (**slot).as_object().is_none() {
+            *slot = Box::new(Object::new());
+        }
+        object = (**slot).as_object_mut().expect("slot holds an Object");
+    }
+    if !object.sealed || object.map.contains_key(leaf) {
+        object.map.insert(leaf.clone(), value);
+    }
+}
+
+fn remove_path(object: &mut Object, path: &[String]) {
+    let Some((leaf, parents)) = path.split_last() else {
+        return;
+    };
+    let mut object = object;
+    for segment in parents {
+        match object
+            .map
+            .get_mut(segment)
+            .and_then(|v| (**v).as_object_mut())
+        {
+            Some(nested) => object = nested,
+            None => return,
+        }
+    }
+    object.map.remove(leaf);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn patch_round_trips_nested_changes() {
+        let mut obj = object!({
+            keep: 1,
+            drop: 2,
+            retyped: 3,
+            nested: {
+                same: true,
+                changed: "old",
+            },
+            scalar: 4,
+        });
+        let target = || {
+            object!({
+                keep: 1,
+                retyped: "three",
+                nested: {
+                    same: true,
+                    changed: "new",
+                    added: 5,
+                },
+                scalar: {
+                    now: "object",
+                },
+            })
+        };
+        let patch = obj.make_patch(target());
+        let paths: Vec<_> = patch
+            .ops()
+            .iter()
+            .map(|op| match op {
+                PatchOp::Set { path, .. } => format!("set {}", path.join(".")),
+                PatchOp::Remove { path } => format!("remove {}", path.join(".")),
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "remove drop",
+                "set nested.added",
+                "set nested.changed",
+                "set retyped",
+                "set scalar",
+            ]
+        );
+        obj.apply_patch(patch);
+        assert!(obj.deep_eq(&target()));
+    }
+
+    #[test]
+    fn patch_round_trips_dotted_keys() {
+        let mut obj = object!({
+            a: {
+                b: 1,
+            },
+        });
+        obj.insert("a.b", 2);
+        obj.insert("c.d", 3);
+        let target = || {
+            let mut target = object!({
+                a: {
+                    b: 1,
+                },
+            });
+            target.insert("a.b", 4);
+            target
+        };
+        let patch = obj.make_patch(target());
+        assert_eq!(
+            patch.ops()[0],
+            PatchOp::Remove {
+                path: vec!["c.d".to_string()],
+            }
+        );
+        obj.apply_patch(patch);
+        assert!(obj.deep_eq(&target()));
+    }
+}
@@ -0,0 +1,148 @@
+//! Human-friendly, indented rendering of an `Object`.
+
+use std::fmt::{self, Write};
+
+use crate::{AnyType, Object};
+
+/// Options for [`Object::pretty_print`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyOptions {
+    /// The number of spaces per level of indentation. Defaults to 2.
+    pub indent: usize,
+    /// Whether keys are written in double quotes. Defaults to `false`.
+    pub quote_keys: bool,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            quote_keys: false,
+        }
+    }
+}
+
+impl Object {
+    /// Renders the `Object` as an indented, multi-line string.
+    ///
+    /// Nested objects and arrays stored as `Vec<Box<dyn AnyType>>` are expanded one entry per
+    /// line; every other value is written with its `Debug` representation. Entries are always
+    /// written in key order, since that is the order in which they are stored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, PrettyOptions};
+    /// let object = object!({
+    ///     name: "app",
+    ///     server: {
+    ///         port: 8080,
+    ///     },
+    /// });
+    /// let options = PrettyOptions {
+    ///     indent: 4,
+    ///     quote_keys: true,
+    /// };
+    /// assert_eq!(
+    ///     object.pretty_print(options),
+    ///     "{\n    \"name\": \"app\",\n    \"server\": {\n        \"port\": 8080,\n    },\n}"
+    /// );
+    /// ```
+    pub fn pretty_print(&self, options: PrettyOptions) -> String {
+        let mut printer = PrettyPrinter {
+            out: String::new(),
+            options,
+            depth: 0,
+        };
+        printer
+            .write_object(self)
+            .expect("writing to a String never fails");
+        printer.out
+    }
+}
+
+struct PrettyPrinter {
+    out: String,
+    options: PrettyOptions,
+    depth: usize,
+}
+
+impl PrettyPrinter {
+    fn write_object(&mut self, object: &Object) -> fmt::Result {
+        if object.map.is_empty() {
+            return self.out.write_str("{}");
+        }
+        self.out.write_char('{')?;
+        self.depth += 1;
+        for (key, value) in &object.map {
+            self.newline()?;
+            if self.options.quote_keys {
+                write!(self.out, "{key:?}: ")?;
+            } else {
+                write!(self.out, "{key}: ")?;
+            }
+            self.write_value(&**value)?;
+            self.out.write_char(',')?;
+        }
+        self.depth -= 1;
+        self.newline()?;
+        self.out.write_char('}')
+    }
+
+    fn write_array(&mut self, array: &[Box<dyn AnyType>]) -> fmt::Result {
+        if array.is_empty() {
+            return self.out.write_str("[]");
+        }
+        self.out.write_char('[')?;
+        self.depth += 1;
+        for value in array {
+            self.newline()?;
+            self.write_value(&**value)?;
+            self.out.write_char(',')?;
+        }
+        self.depth -= 1;
+        self.newline()?;
+        self.out.write_char(']')
+    }
+
+    fn write_value(&mut self, value: &dyn AnyType) -> fmt::Result {
+        if let Some(object) = value.downcast_ref::<Object>() {
+            self.write_object(object)
+        } else if let Some(array) = value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+            self.write_array(array)
+        } else {
+            write!(self.out, "{value:?}")
+        }
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        write!(
+            self.out,
+            "\n{:width$}",
+            "",
+            width = self.depth * self.options.indent
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn expands_objects_and_arrays() {
+        let object = object!({
+            empty: {},
+            list: vec![Box::new(1) as Box<dyn AnyType>, Box::new("two")],
+            nothing: Vec::<Box<dyn AnyType>>::new(),
+            pair: (1, 2),
+        });
+        assert_eq!(
+            object.pretty_print(PrettyOptions::default()),
+            "{\n  empty: {},\n  list: [\n    1,\n    \"two\",\n  ],\n  nothing: [],\n  pair: (1, 2),\n}"
+        );
+        assert_eq!(Object::new().pretty_print(PrettyOptions::default()), "{}");
+    }
+}
@@ -0,0 +1,292 @@
+//! A global registry for retrieving stored values as trait objects.
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::{OnceLock, PoisonError, RwLock},
+};
+
+use crate::Object;
+
+type Caster<Tr> = Box<dyn for<'a> Fn(&'a dyn Any) -> Option<&'a Tr> + Send + Sync>;
+
+type Registry = RwLock<HashMap<(TypeId, TypeId), Box<dyn Any + Send + Sync>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Registers `T` as an implementor of the trait object type `Tr`.
+///
+/// `cast` converts a `&T` into a `&Tr`. Since Rust can't express "`T` implements `Tr`" generically,
+/// it has to be supplied by the caller, but an identity closure is all it takes: `|v| v`.
+///
+/// Once registered, values of type `T` can be retrieved as `&Tr` with [`Object::get_as_trait`].
+/// Registering the same pair again replaces the previous cast.
+///
+/// # Thread Safety
+///
+/// The registry is global and protected by a `RwLock`. Registrations are visible to every thread as
+/// soon as this function returns, and lookups from several threads can proceed concurrently.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{object, register_trait};
+///
+/// trait Greet {
+///     fn greet(&self) -> String;
+/// }
+///
+/// #[derive(Debug, PartialEq, PartialOrd)]
+/// struct English;
+///
+/// impl Greet for English {
+///     fn greet(&self) -> String {
+///         "hello".to_string()
+///     }
+/// }
+///
+/// register_trait::<English, dyn Greet>(|v| v);
+/// let obj = object!({
+///     greeter: English,
+/// });
+/// let greeter = obj.get_as_trait::<dyn Greet>("greeter").unwrap();
+/// assert_eq!(greeter.greet(), "hello");
+/// ```
+pub fn register_trait<T: 'static, Tr: ?Sized + 'static>(cast: fn(&T) -> &Tr) {
+    let caster: Caster<Tr> = Box::new(move |value| value.downcast_ref::<T>().map(cast));
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert((TypeId::of::<T>(), TypeId::of::<Tr>()), Box::new(caster));
+}
+
+pub(crate) fn cast<Tr: ?Sized + 'static>(value: &dyn Any) -> Option<&Tr> {
+    let registry = registry().read().unwrap_or_else(PoisonError::into_inner);
+    let caster = registry
+        .get(&(Any::type_id(value), TypeId::of::<Tr>()))?
+        .downcast_ref::<Caster<Tr>>()?;
+    caster(value)
+}
+
+impl Object {
+    /// Returns the value corresponding to the key as the trait object type `Tr`.
+    ///
+    /// Returns `None` if the key is absent or if the concrete type of the value was not
+    /// registered for `Tr` with [`register_trait`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::Display;
+    /// use dynamic_object::{object, register_trait};
+    ///
+    /// register_trait::<i32, dyn Display>(|v| v);
+    /// let obj = object!({
+    ///     number: 42,
+    ///     float: 1.5,
+    /// });
+    /// assert_eq!(obj.get_as_trait::<dyn Display>("number").unwrap().to_string(), "42");
+    /// assert!(obj.get_as_trait::<dyn Display>("float").is_none());
+    /// ```
+    pub fn get_as_trait<Tr: ?Sized + 'static>(&self, key: &str) -> Option<&Tr> {
+        cast((**self.map.get(key)?).as_any())
+    }
+
+    /// Returns an iterator over the values that can be retrieved as the trait object type `Tr`,
+    /// in key order.
+    ///
+    /// Only values whose concrete type was registered for `Tr` with [`register_trait`] are
+    /// yielded; every other value is skipped. Register the types before iterating, since the
+    /// registry is consulted once per value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fmt::Display;
+    /// use dynamic_object::{object, register_trait};
+    ///
+    /// register_trait::<i32, dyn Display>(|v| v);
+    /// register_trait::<&str, dyn Display>(|v| v);
+    /// let obj = object!({
+    ///     a: 1,
+    ///     b: "two",
+    ///     c: vec![3],
+    /// });
+    /// let rendered: Vec<_> = obj
+    ///     .iter_as_trait::<dyn Display>()
+    ///     .map(|(key, value)| format!("{key}={value}"))
+    ///     .collect();
+    /// assert_eq!(rendered, ["a=1", "b=two"]);
+    /// ```
+    pub fn iter_as_trait<Tr: ?Sized + 'static>(&self) -> impl Iterator<Item = (&String, &Tr)> {
+        self.map
+            .iter()
+            .filter_map(|(key, value)| Some((key, cast::<Tr>((**value).as_any())?)))
+    }
+
+    /// Collects the values that can be retrieved as the trait object type `Tr`, in key order.
+    ///
+    /// This is [`Object::iter_as_trait`] collected into a `Vec`, e.g. for calling a trait method
+    /// on every matching value. Only values whose concrete type was registered for `Tr` with
+    /// [`register_trait`] appear; values of unregistered types are left out even if they
+    /// implement `Tr`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, register_trait};
+    ///
+    /// trait Render {
+    ///     fn render(&self) -> String;
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq, PartialOrd)]
+    /// struct Button(&'static str);
+    ///
+    /// impl Render for Button {
+    ///     fn render(&self) -> String {
+    ///         format!("<button>{}</button>", self.0)
+    ///     }
+    /// }
+    ///
+    /// register_trait::<Button, dyn Render>(|v| v);
+    /// let page = object!({
+    ///     ok: Button("OK"),
+    ///     title: "Confirm",
+    ///     cancel: Button("Cancel"),
+    /// });
+    /// let html: Vec<_> = page
+    ///     .collect_as_trait::<dyn Render>()
+    ///     .into_iter()
+    ///     .map(|(_, widget)| widget.render())
+    ///     .collect();
+    /// assert_eq!(html, ["<button>Cancel</button>", "<button>OK</button>"]);
+    /// ```
+    pub fn collect_as_trait<Tr: ?Sized + 'static>(&self) -> Vec<(&String, &Tr)> {
+        self.iter_as_trait().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, PartialOrd)]
+    struct Square(f64);
+
+    #[derive(Debug, PartialEq, PartialOrd)]
+    struct Circle(f64);
+
+    #[test]
+    fn casts_registered_types_only() {
+        trait Area {
+            fn area(&self) -> f64;
+        }
+
+        impl Area for Square {
+            fn area(&self) -> f64 {
+                self.0 * self.0
+            }
+        }
+
+        impl Area for Circle {
+            fn area(&self) -> f64 {
+                3.0 * self.0 * self.0
+            }
+        }
+
+        register_trait::<Square, dyn Area>(|v| v);
+        let obj = object!({
+            square: Square(2.0),
+            circle: Circle(1.0),
+        });
+        assert_eq!(
+            obj.get_as_trait::<dyn Area>("square").map(Area::area),
+            Some(4.0)
+        );
+        assert!(obj.get_as_trait::<dyn Area>("circle").is_none());
+        assert!(obj.get_as_trait::<dyn Area>("missing").is_none());
+
+        register_trait::<Circle, dyn Area>(|v| v);
+        assert_eq!(
+            obj.get_as_trait::<dyn Area>("circle").map(Area::area),
+            Some(3.0)
+        );
+    }
+
+    #[test]
+    fn iterates_registered_types_only() {
+        trait Volume {
+            fn volume(&self) -> f64;
+        }
+
+        #[derive(Debug, PartialEq, PartialOrd)]
+        struct Cube(f64);
+
+        #[derive(Debug, PartialEq, PartialOrd)]
+        struct Sphere(f64);
+
+        impl Volume for Cube {
+            fn volume(&self) -> f64 {
+                self.0 * self.0 * self.0
+            }
+        }
+
+        impl Volume for Sphere {
+            fn volume(&self) -> f64 {
+                4.0 * self.0 * self.0 * self.0
+            }
+        }
+
+        register_trait::<Cube, dyn Volume>(|v| v);
+        register_trait::<Sphere, dyn Volume>(|v| v);
+        let obj = object!({
+            a: Cube(1.0),
+            b: 2.0,
+            c: Sphere(1.0),
+        });
+        let volumes: Vec<_> = obj
+            .iter_as_trait::<dyn Volume>()
+            .map(|(key, solid)| (key.as_str(), solid.volume()))
+            .collect();
+        assert_eq!(volumes, [("a", 1.0), ("c", 4.0)]);
+    }
+
+    trait Perimeter {
+        fn perimeter(&self) -> f64;
+    }
+
+    impl Perimeter for Square {
+        fn perimeter(&self) -> f64 {
+            4.0 * self.0
+        }
+    }
+
+    impl Perimeter for Circle {
+        fn perimeter(&self) -> f64 {
+            6.0 * self.0
+        }
+    }
+
+    #[test]
+    fn collects_registered_types_only() {
+        register_trait::<Square, dyn Perimeter>(|v| v);
+        let obj = object!({
+            a: Square(1.0),
+            b: Circle(1.0),
+            c: Square(2.0),
+        });
+        let perimeters: Vec<_> = obj
+            .collect_as_trait::<dyn Perimeter>()
+            .into_iter()
+            .map(|(key, shape)| (key.as_str(), shape.perimeter()))
+            .collect();
+        assert_eq!(perimeters, [("a", 4.0), ("c", 8.0)]);
+        assert!(object!({}).collect_as_trait::<dyn Perimeter>().is_empty());
+    }
+}
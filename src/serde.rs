@@ -0,0 +1,580 @@
+//! Self-describing `serde` support for `Object`.
+//!
+//! Every value is written as a map with a `$type` tag and a `$value`, e.g. `{"$type": "i64",
+//! "$value": 5}`, so an `Object` can be deserialized back without knowing its types in advance.
+
+use std::fmt::{self, Formatter};
+
+use ::serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{AnyType, Array, Null, Object, ObjectPatch, PatchOp};
+
+const TYPE: &str = "$type";
+const VALUE: &str = "$value";
+const OP: &str = "op";
+const PATH: &str = "path";
+const PATCH_VALUE: &str = "value";
+
+/// Serializes the `Object` as a map from keys to tagged values.
+///
+/// The tags are `bool`, `char`, `string` (for `String` and `&str`), `unit` (for `()`), `null`
+/// (for [`Null`]), `object`, `array` (for `Vec<Box<dyn AnyType>>`) and the names of the integer
+/// and float primitives. Serializing any other value fails with an error naming its type.
+///
+/// This implementation requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::object;
+/// let obj = object!({
+///     count: 5i64,
+/// });
+/// assert_eq!(
+///     serde_json::to_string(&obj).unwrap(),
+///     r#"{"count":{"$type":"i64","$value":5}}"#
+/// );
+/// ```
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TaggedObject(self, Object::DEFAULT_MAX_DEPTH).serialize(serializer)
+    }
+}
+
+/// An `Object` serialized with a custom limit on its nesting depth, created by
+/// [`Object::serialize_max_depth`].
+///
+/// This type requires the `serde` feature.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxDepth<'a> {
+    object: &'a Object,
+    max_depth: usize,
+}
+
+impl Serialize for MaxDepth<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TaggedObject(self.object, self.max_depth).serialize(serializer)
+    }
+}
+
+impl Object {
+    /// Returns a wrapper that serializes the `Object` like its `Serialize` implementation, but
+    /// fails if it is nested deeper than `max_depth`, counted like [`Object::max_depth`].
+    ///
+    /// The `Serialize` implementation of `Object` itself uses [`Object::DEFAULT_MAX_DEPTH`].
+    ///
+    /// This method requires the `serde` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let obj = object!({
+    ///     a: {
+    ///         b: 1,
+    ///     },
+    /// });
+    /// assert!(serde_json::to_string(&obj.serialize_max_depth(2)).is_ok());
+    /// let error = serde_json::to_string(&obj.serialize_max_depth(1)).unwrap_err();
+    /// assert!(error.to_string().contains("maximum nesting depth"));
+    /// ```
+    pub fn serialize_max_depth(&self, max_depth: usize) -> MaxDepth<'_> {
+        MaxDepth {
+            object: self,
+            max_depth,
+        }
+    }
+}
+
+/// Deserializes an `Object` written by its `Serialize` implementation.
+///
+/// In each tagged value, `$type` must come before `$value`. `string` values are stored as
+/// `String`. An unknown `$type` fails with an error naming the tag.
+///
+/// This implementation requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::Object;
+/// let json = r#"{"name":{"$type":"string","$value":"app"},"port":{"$type":"u16","$value":80}}"#;
+/// let obj: Object = serde_json::from_str(json).unwrap();
+/// assert_eq!(obj.get_as::<String>("name").unwrap(), "app");
+/// assert_eq!(obj.get_as::<u16>("port"), Some(&80));
+///
+/// let error = serde_json::from_str::<Object>(r#"{"a":{"$type":"i256","$value":1}}"#);
+/// assert!(error.unwrap_err().to_string().contains("unknown `$type` tag `i256`"));
+/// ```
+impl<'de> Deserialize<'de> for Object {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+/// An error for an object or array nested deeper than allowed.
+fn too_deep<E: ser::Error>() -> E {
+    E::custom("value exceeds the maximum nesting depth")
+}
+
+/// The entries of an `Object`, which may hold objects and arrays nested up to `.1` levels deep,
+/// counting itself.
+struct TaggedObject<'a>(&'a Object, usize);
+
+impl Serialize for TaggedObject<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let depth = self.1.checked_sub(1).ok_or_else(too_deep)?;
+        let mut map = serializer.serialize_map(Some(self.0.map.len()))?;
+        for (key, value) in &self.0.map {
+            map.serialize_entry(key, &Tagged(&**value, depth))?;
+        }
+        map.end()
+    }
+}
+
+/// A value, which may be an object or array nested up to `.1` levels deep, counting itself.
+struct Tagged<'a>(&'a dyn AnyType, usize);
+
+impl Serialize for Tagged<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        macro_rules! tagged {
+            ($($ty:ty => $tag:literal),* $(,)?) => {
+                $(
+                    if let Some(value) = self.0.downcast_ref::<$ty>() {
+                        return tag(serializer, $tag, value);
+                    }
+                )*
+            };
+        }
+        tagged! {
+            bool => "bool",
+            char => "char",
+            String => "string",
+            &str => "string",
+            () => "unit",
+            Null => "null",
+            i8 => "i8",
+            i16 => "i16",
+            i32 => "i32",
+            i64 => "i64",
+            i128 => "i128",
+            isize => "isize",
+            u8 => "u8",
+            u16 => "u16",
+            u32 => "u32",
+            u64 => "u64",
+            u128 => "u128",
+            usize => "usize",
+            f32 => "f32",
+            f64 => "f64",
+        }
+        if let Some(object) = self.0.downcast_ref::<Object>() {
+            return tag(serializer, "object", &TaggedObject(object, self.1));
+        }
+        if let Some(array) = self.0.downcast_ref::<Array>() {
+            return tag(serializer, "array", &TaggedArray(array, self.1));
+        }
+        Err(ser::Error::custom(format_args!(
+            "values of type `{}` can't be serialized",
+            self.0.dyn_type_name()
+        )))
+    }
+}
+
+fn tag<S: Serializer>(serializer: S, tag: &str, value: &impl Serialize) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(2))?;
+    map.serialize_entry(TYPE, tag)?;
+    map.serialize_entry(VALUE, value)?;
+    map.end()
+}
+
+/// The elements of an array, which may hold objects and arrays nested up to `.1` levels deep,
+/// counting itself.
+struct TaggedArray<'a>(&'a Array, usize);
+
+impl Serialize for TaggedArray<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let depth = self.1.checked_sub(1).ok_or_else(too_deep)?;
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for value in self.0 {
+            seq.serialize_element(&Tagged(&**value, depth))?;
+        }
+        seq.end()
+    }
+}
+
+impl Serialize for Null {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de> Deserialize<'de> for Null {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer).map(|()| Null)
+    }
+}
+
+/// Serializes the patch as a sequence of operations.
+///
+/// Each operation is a map with an `op` (`"set"` or `"remove"`) and a `path`; `set` operations
+/// also have a `value`, tagged like the values of an `Object`.
+///
+/// This implementation requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::object;
+/// let patch = object!({ a: 1, b: 2, }).make_patch(object!({ b: 3u8, }));
+/// assert_eq!(
+///     serde_json::to_string(&patch).unwrap(),
+///     r#"[{"op":"remove","path":["a"]},{"op":"set","path":["b"],"value":{"$type":"u8","$value":3}}]"#
+/// );
+/// ```
+impl Serialize for ObjectPatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.ops.len()))?;
+        for op in &self.ops {
+            seq.serialize_element(op)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes a patch written by its `Serialize` implementation.
+///
+/// In each operation, `op` must come first, followed by `path` and, for `set`, `value`.
+///
+/// This implementation requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{object, ObjectPatch};
+/// let json = r#"[{"op":"set","path":["a","b"],"value":{"$type":"bool","$value":true}}]"#;
+/// let patch: ObjectPatch = serde_json::from_str(json).unwrap();
+/// let mut obj = object!({});
+/// obj.apply_patch(patch);
+/// assert_eq!(obj, object!({ a: { b: true, }, }));
+/// ```
+impl<'de> Deserialize<'de> for ObjectPatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(ObjectPatch::from(Vec::<PatchOp>::deserialize(
+            deserializer,
+        )?))
+    }
+}
+
+/// Serializes the operation as a map with an `op`, a `path` as a list of keys and, for `set`, a
+/// tagged `value`.
+///
+/// This implementation requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::PatchOp;
+/// let op = PatchOp::Remove { path: vec!["a.b".to_string()] };
+/// assert_eq!(serde_json::to_string(&op).unwrap(), r#"{"op":"remove","path":["a.b"]}"#);
+/// ```
+impl Serialize for PatchOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PatchOp::Set { path, value } => {
+                let mut map = serializer.serialize_map(Some(3))?;
+                map.serialize_entry(OP, "set")?;
+                map.serialize_entry(PATH, path)?;
+                map.serialize_entry(PATCH_VALUE, &Tagged(&**value, Object::DEFAULT_MAX_DEPTH))?;
+                map.end()
+            }
+            PatchOp::Remove { path } => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry(OP, "remove")?;
+                map.serialize_entry(PATH, path)?;
+                map.end()
+            }
+        }
+    }
+}
+
+/// Deserializes an operation written by its `Serialize` implementation.
+///
+/// This implementation requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::PatchOp;
+/// let op: PatchOp = serde_json::from_str(r#"{"op":"remove","path":["a"]}"#).unwrap();
+/// assert_eq!(op, PatchOp::Remove { path: vec!["a".to_string()] });
+/// ```
+impl<'de> Deserialize<'de> for PatchOp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(PatchOpVisitor)
+    }
+}
+
+struct ObjectVisitor;
+
+impl<'de> Visitor<'de> for ObjectVisitor {
+    type Value = Object;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of tagged values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut object = Object::new();
+        while let Some((key, Untagged(value))) = access.next_entry::<String, Untagged>()? {
+            object.map.insert(key, value);
+        }
+        Ok(object)
+    }
+}
+
+struct Untagged(Box<dyn AnyType>);
+
+impl<'de> Deserialize<'de> for Untagged {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(UntaggedVisitor)
+    }
+}
+
+struct UntaggedVisitor;
+
+impl<'de> Visitor<'de> for UntaggedVisitor {
+    type Value = Untagged;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a map with a `$type` and a `$value`")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        match access.next_key::<String>()? {
+            Some(key) if key == TYPE => {}
+            _ => return Err(de::Error::missing_field(TYPE)),
+        }
+        let tag = access.next_value::<String>()?;
+        match access.next_key::<String>()? {
+            Some(key) if key == VALUE => {}
+            _ => return Err(de::Error::missing_field(VALUE)),
+        }
+        macro_rules! untagged {
+            ($($tag:literal => $ty:ty),* $(,)?) => {
+                match tag.as_str() {
+                    $($tag => Box::new(access.next_value::<$ty>()?) as Box<dyn AnyType>,)*
+                    "array" => {
+                        let TaggedElements(array) = access.next_value()?;
+                        Box::new(array)
+                    }
+                    _ => {
+                        return Err(de::Error::custom(format_args!(
+                            "unknown `{TYPE}` tag `{tag}`"
+                        )))
+                    }
+                }
+            };
+        }
+        let value = untagged! {
+            "bool" => bool,
+            "char" => char,
+            "string" => String,
+            "unit" => (),
+            "null" => Null,
+            "i8" => i8,
+            "i16" => i16,
+            "i32" => i32,
+            "i64" => i64,
+            "i128" => i128,
+            "isize" => isize,
+            "u8" => u8,
+            "u16" => u16,
+            "u32" => u32,
+            "u64" => u64,
+            "u128" => u128,
+            "usize" => usize,
+            "f32" => f32,
+            "f64" => f64,
+            "object" => Object,
+        };
+        if access.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::custom(format_args!(
+                "unexpected key after `{VALUE}`"
+            )));
+        }
+        Ok(Untagged(value))
+    }
+}
+
+struct PatchOpVisitor;
+
+impl<'de> Visitor<'de> for PatchOpVisitor {
+    type Value = PatchOp;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a map with an `op`, a `path` and an optional `value`")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        match access.next_key::<String>()? {
+            Some(key) if key == OP => {}
+            _ => return Err(de::Error::missing_field(OP)),
+        }
+        let op = access.next_value::<String>()?;
+        match access.next_key::<String>()? {
+            Some(key) if key == PATH => {}
+            _ => return Err(de::Error::missing_field(PATH)),
+        }
+        let path = access.next_value::<Vec<String>>()?;
+        let op = match op.as_str() {
+            "set" => {
+                match access.next_key::<String>()? {
+                    Some(key) if key == PATCH_VALUE => {}
+                    _ => return Err(de::Error::missing_field(PATCH_VALUE)),
+                }
+                let Untagged(value) = access.next_value()?;
+                PatchOp::Set { path, value }
+            }
+            "remove" => PatchOp::Remove { path },
+            _ => {
+                return Err(de::Error::unknown_variant(&op, &["set", "remove"]));
+            }
+        };
+        if access.next_key::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::custom("unexpected key in patch operation"));
+        }
+        Ok(op)
+    }
+}
+
+struct TaggedElements(Array);
+
+impl<'de> Deserialize<'de> for TaggedElements {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(TaggedElementsVisitor)
+    }
+}
+
+struct TaggedElementsVisitor;
+
+impl<'de> Visitor<'de> for TaggedElementsVisitor {
+    type Value = TaggedElements;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence of tagged values")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut array = Array::new();
+        while let Some(Untagged(value)) = access.next_element()? {
+            array.push(value);
+        }
+        Ok(TaggedElements(array))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_built_in_types() {
+        let obj = object!({
+            flag: true,
+            letter: 'x',
+            text: "text",
+            unit: (),
+            null: Null,
+            small: -3i8,
+            big: u128::MAX,
+            float: 0.5f32,
+            list: vec![Box::new(1u8) as Box<dyn AnyType>, Box::new(object!({ a: 2, }))],
+            nested: {
+                inner: 1.5,
+            },
+        });
+        let json = serde_json::to_string(&obj).unwrap();
+        let back: Object = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(back.get_as::<String>("text").unwrap(), "text");
+        assert_eq!(back.get_as::<u128>("big"), Some(&u128::MAX));
+        assert_eq!(back.get_as::<Null>("null"), Some(&Null));
+        assert_eq!(back.get_as::<()>("unit"), Some(&()));
+        let list = back.get_as_slice::<Box<dyn AnyType>>("list").unwrap();
+        assert_eq!(list[0].downcast_ref::<u8>(), Some(&1));
+        assert_eq!(list[1].downcast_ref::<Object>(), Some(&object!({ a: 2, })));
+        back.map
+            .keys()
+            .filter(|k| *k != "text")
+            .for_each(|k| assert_eq!(back.get(k), obj.get(k), "{k}"));
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_tags() {
+        #[derive(Debug, PartialEq, PartialOrd)]
+        struct Custom;
+
+        let error = serde_json::to_string(&object!({ a: Custom, })).unwrap_err();
+        assert!(error.to_string().contains("Custom` can't be serialized"));
+
+        let error = serde_json::from_str::<Object>(r#"{"a":{"$value":1,"$type":"i32"}}"#);
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("missing field `$type`"));
+
+        let error = serde_json::from_str::<Object>(r#"{"a":{"$type":"bytes","$value":[]}}"#);
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("unknown `$type` tag `bytes`"));
+    }
+
+    #[test]
+    fn round_trips_patches() {
+        let mut obj = object!({
+            a: 1,
+            b: {
+                c: 'x',
+            },
+        });
+        let patch = obj.make_patch(object!({ b: { c: 'y', d: Null, }, }));
+        let json = serde_json::to_string(&patch).unwrap();
+        let back: ObjectPatch = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, patch);
+
+        obj.apply_patch(back);
+        assert_eq!(obj, object!({ b: { c: 'y', d: Null, }, }));
+
+        let error = serde_json::from_str::<ObjectPatch>(r#"[{"op":"move","path":["a"]}]"#);
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("unknown variant `move`"));
+        let error = serde_json::from_str::<ObjectPatch>(r#"[{"op":"set","path":["a"]}]"#);
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("missing field `value`"));
+    }
+
+    #[test]
+    fn limits_depth() {
+        let obj = object!({
+            list: vec![Box::new(object!({ a: 1, })) as Box<dyn AnyType>],
+        });
+        assert_eq!(obj.max_depth(), 3);
+        assert_eq!(
+            serde_json::to_string(&obj.serialize_max_depth(3)).unwrap(),
+            serde_json::to_string(&obj).unwrap()
+        );
+        let error = serde_json::to_string(&obj.serialize_max_depth(2)).unwrap_err();
+        assert!(error.to_string().contains("maximum nesting depth"));
+    }
+}
@@ -0,0 +1,237 @@
+//! Conversions between `Object` and TOML values.
+
+use ::toml::{value::Datetime, Table, Value};
+
+use crate::{AnyType, ConversionError, Object};
+
+impl Object {
+    /// Converts a TOML table into an `Object`.
+    ///
+    /// Tables become nested `Object`s and arrays become `Vec<Box<dyn AnyType>>`. Leaves are stored as
+    /// `String`, `i64`, `f64`, `bool` or `toml::value::Datetime`.
+    ///
+    /// This method requires the `toml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let table = "port = 8080\n[db]\nuser = \"admin\"".parse::<toml::Table>().unwrap();
+    /// let config = Object::from_toml(table);
+    /// assert_eq!(config.get_as::<i64>("port"), Some(&8080));
+    /// ```
+    pub fn from_toml(table: Table) -> Self {
+        let mut object = Object::new();
+        for (key, value) in table {
+            object.map.insert(key, from_toml_value(value));
+        }
+        object
+    }
+
+    /// Converts the `Object` into a TOML table.
+    ///
+    /// Besides the types produced by [`Object::from_toml`], `&str`, `char` and every integer and
+    /// float primitive are accepted. Integers that don't fit in an `i64` result in
+    /// `ConversionError::OutOfRange`, and any other value in `ConversionError::Unsupported`.
+    ///
+    /// This method requires the `toml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let config = object!({
+    ///     port: 8080,
+    ///     db: {
+    ///         user: "admin",
+    ///     },
+    /// });
+    /// let table = config.to_toml().unwrap();
+    /// assert_eq!(table["db"]["user"].as_str(), Some("admin"));
+    /// ```
+    pub fn to_toml(&self) -> Result<Table, ConversionError> {
+        to_toml_table(self, &mut Vec::new())
+    }
+
+    /// Converts the `Object` into a `toml::Value`, which is always a `Value::Table`.
+    ///
+    /// This is [`Object::to_toml`] for code that works with `toml::Value`s, such as when embedding
+    /// the `Object` in a larger document.
+    ///
+    /// This method requires the `toml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let config = object!({ port: 8080 });
+    /// let value = config.to_toml_value().unwrap();
+    /// assert_eq!(value["port"].as_integer(), Some(8080));
+    /// ```
+    pub fn to_toml_value(&self) -> Result<Value, ConversionError> {
+        self.to_toml().map(Value::Table)
+    }
+}
+
+impl From<Table> for Object {
+    fn from(table: Table) -> Self {
+        Object::from_toml(table)
+    }
+}
+
+fn from_toml_value(value: Value) -> Box<dyn AnyType> {
+    match value {
+        Value::String(s) => Box::new(s),
+        Value::Integer(n) => Box::new(n),
+        Value::Float(n) => Box::new(n),
+        Value::Boolean(b) => Box::new(b),
+        Value::Datetime(d) => Box::new(d),
+        Value::Array(array) => Box::new(
+            array
+                .into_iter()
+                .map(from_toml_value)
+                .collect::<Vec<Box<dyn AnyType>>>(),
+        ),
+        Value::Table(table) => Box::new(Object::from_toml(table)),
+    }
+}
+
+fn to_toml_table(object: &Object, path: &mut Vec<String>) -> Result<Table, ConversionError> {
+    let mut table = Table::new();
+    for (key, value) in &object.map {
+        path.push(key.clone());
+        table.insert(key.clone(), to_toml_value(&**value, path)?);
+        path.pop();
+    }
+    Ok(table)
+}
+
+macro_rules! convert_integers {
+    ($value:ident, $path:ident, $($t:ty),*) => {
+        $(
+            if let Some(n) = $value.downcast_ref::<$t>() {
+                return i64::try_from(*n)
+                    .map(Value::Integer)
+                    .map_err(|_| ConversionError::OutOfRange { path: $path.join(".") });
+            }
+        )*
+    };
+}
+
+fn to_toml_value(value: &dyn AnyType, path: &mut Vec<String>) -> Result<Value, ConversionError> {
+    if let Some(object) = value.downcast_ref::<Object>() {
+        return to_toml_table(object, path).map(Value::Table);
+    }
+    if let Some(array) = value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+        let mut values = Vec::with_capacity(array.len());
+        for (i, value) in array.iter().enumerate() {
+            path.push(i.to_string());
+            values.push(to_toml_value(&**value, path)?);
+            path.pop();
+        }
+        return Ok(Value::Array(values));
+    }
+    if let Some(s) = value.downcast_ref::<String>() {
+        return Ok(Value::String(s.clone()));
+    }
+    if let Some(s) = value.downcast_ref::<&str>() {
+        return Ok(Value::String(s.to_string()));
+    }
+    if let Some(c) = value.downcast_ref::<char>() {
+        return Ok(Value::String(c.to_string()));
+    }
+    if let Some(b) = value.downcast_ref::<bool>() {
+        return Ok(Value::Boolean(*b));
+    }
+    if let Some(d) = value.downcast_ref::<Datetime>() {
+        return Ok(Value::Datetime(*d));
+    }
+    if let Some(n) = value.downcast_ref::<f64>() {
+        return Ok(Value::Float(*n));
+    }
+    if let Some(n) = value.downcast_ref::<f32>() {
+        return Ok(Value::Float(f64::from(*n)));
+    }
+    convert_integers!(value, path, i64, i8, i16, i32, i128, isize, u8, u16, u32, u64, u128, usize);
+    Err(ConversionError::Unsupported {
+        path: path.join("."),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        title = "example"
+        released = 1979-05-27T07:32:00Z
+
+        [server]
+        ports = [8000, 8001]
+        ratio = 0.5
+        enabled = true
+
+        [[users]]
+        name = "a"
+
+        [[users]]
+        name = "b"
+    "#;
+
+    #[test]
+    fn round_trips_toml_tables() {
+        let table = CONFIG.parse::<Table>().unwrap();
+        let object = Object::from_toml(table.clone());
+
+        assert_eq!(
+            object.get_as::<String>("title"),
+            Some(&"example".to_string())
+        );
+        assert!(object.get_as::<Datetime>("released").is_some());
+        let server = object.get_as::<Object>("server").unwrap();
+        assert_eq!(server.get_as::<f64>("ratio"), Some(&0.5));
+        let ports = server.get_as::<Vec<Box<dyn AnyType>>>("ports").unwrap();
+        assert_eq!(ports[1].downcast_ref::<i64>(), Some(&8001));
+        let users = object.get_as::<Vec<Box<dyn AnyType>>>("users").unwrap();
+        let user = users[0].downcast_ref::<Object>().unwrap();
+        assert_eq!(user.get_as::<String>("name"), Some(&"a".to_string()));
+
+        assert_eq!(object.to_toml(), Ok(table.clone()));
+        assert_eq!(object.to_toml_value(), Ok(Value::Table(table)));
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        let object = object!({
+            small: 1u8,
+            name: "name",
+        });
+        let table = object.to_toml().unwrap();
+        assert_eq!(table["small"], Value::Integer(1));
+        assert_eq!(table["name"], Value::String("name".to_string()));
+
+        let object = object!({
+            a: {
+                big: u64::MAX,
+            },
+        });
+        assert_eq!(
+            object.to_toml(),
+            Err(ConversionError::OutOfRange {
+                path: "a.big".to_string()
+            })
+        );
+
+        let object = object!({
+            unit: (),
+        });
+        assert_eq!(
+            object.to_toml(),
+            Err(ConversionError::Unsupported {
+                path: "unit".to_string()
+            })
+        );
+    }
+}
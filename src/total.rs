@@ -0,0 +1,122 @@
+//! A totally ordered `Object` wrapper.
+
+use std::{
+    cmp::Ordering,
+    ops::{Deref, DerefMut},
+};
+
+use crate::Object;
+
+/// An `Object` with a total order, so it can be used as a `BTreeMap` key or in a `BTreeSet`.
+///
+/// Equality and ordering are both defined by [`Object::cmp_total`], which every stored value
+/// supports through [`AnyType::dyn_total_cmp`](crate::AnyType::dyn_total_cmp). Note that this
+/// means a `TotalObject` holding `NaN` is equal to itself, unlike a plain `Object`. The order is
+/// total for values of the standard numeric types and containers of them, including ones holding
+/// `NaN`; see [`Object::cmp_total`] for the exact rules and their limits for other types.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+/// use dynamic_object::{object, TotalObject};
+/// let mut set = BTreeSet::new();
+/// set.insert(TotalObject::from(object!({ x: f64::NAN, })));
+/// set.insert(TotalObject::from(object!({ x: f64::NAN, })));
+/// set.insert(TotalObject::from(object!({ x: 1.0, })));
+/// assert_eq!(set.len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct TotalObject(pub Object);
+
+impl TotalObject {
+    /// Consumes the `TotalObject`, returning the wrapped `Object`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{Object, TotalObject};
+    /// let object = TotalObject::from(Object::new()).into_inner();
+    /// assert!(object.is_empty());
+    /// ```
+    pub fn into_inner(self) -> Object {
+        self.0
+    }
+}
+
+impl From<Object> for TotalObject {
+    fn from(object: Object) -> Self {
+        Self(object)
+    }
+}
+
+impl PartialEq for TotalObject {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for TotalObject {}
+
+impl PartialOrd for TotalObject {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalObject {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_total(&other.0)
+    }
+}
+
+impl Deref for TotalObject {
+    type Target = Object;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for TotalObject {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::object;
+
+    use super::*;
+
+    #[test]
+    fn works_as_a_map_key() {
+        let mut map = BTreeMap::new();
+        map.insert(TotalObject::from(object!({ a: 2, })), "two");
+        map.insert(TotalObject::from(object!({ a: 1, })), "one");
+        map.insert(TotalObject::from(object!({ a: f64::NAN, })), "nan");
+        map.insert(TotalObject::from(object!({ a: 1, })), "uno");
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map[&TotalObject::from(object!({ a: 1, }))], "uno");
+        assert_eq!(map[&TotalObject::from(object!({ a: f64::NAN, }))], "nan");
+        let values: Vec<_> = map.values().copied().collect();
+        assert_eq!(values, vec!["nan", "uno", "two"]);
+    }
+
+    #[test]
+    fn order_is_transitive_with_nan_in_containers() {
+        let total = |values: Vec<f64>| TotalObject::from(object!({ a: values }));
+        let (nan, one, two) = (total(vec![f64::NAN]), total(vec![1.0]), total(vec![2.0]));
+        assert!(one < two && two < nan && one < nan);
+        assert_ne!(nan, one);
+        assert_ne!(nan, two);
+
+        let mut sorted = [total(vec![2.0]), total(vec![f64::NAN]), total(vec![1.0])];
+        sorted.sort();
+        assert_eq!(sorted, [one, two, nan]);
+    }
+}
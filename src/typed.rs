@@ -0,0 +1,467 @@
+//! Objects whose keys and value types are fixed by a schema checked at compile time.
+
+use std::{any, fmt, marker::PhantomData, ops::Deref};
+
+use crate::{AnyType, Object, SchemaError, TypeConflict};
+
+/// A schema for [`TypedObject`], listing the keys it may hold.
+///
+/// Schemas and their keys are declared together with [`typed_object!`](crate::typed_object).
+pub trait Schema {
+    /// Checks that every key of the schema present in `object` holds a value of its type.
+    fn validate(object: &Object) -> Result<(), TypeConflict>;
+}
+
+/// A key of the schema `S`, with the name it is stored under and the type of its value.
+///
+/// Keys are declared with [`typed_object!`](crate::typed_object), and a key of one schema can't
+/// be used with a [`TypedObject`] of another. The trait is sealed: it can only be implemented
+/// through the macro, which also rejects two keys of a schema with the same name, so every name
+/// maps to a single type.
+pub trait Key<S: Schema>: private::Sealed<S> {
+    /// The name the value is stored under.
+    const NAME: &'static str;
+
+    /// The type of the value.
+    type Value: AnyType;
+
+    /// Checks that `object` holds a value of type `Self::Value` under `Self::NAME`, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, typed_object, Key};
+    ///
+    /// typed_object! {
+    ///     pub schema Server {
+    ///         Port: "port" => u16,
+    ///     }
+    /// }
+    ///
+    /// assert!(Port::check(&object!({})).is_ok());
+    /// assert!(Port::check(&object!({ port: 80u16, })).is_ok());
+    /// assert_eq!(Port::check(&object!({ port: 80, })).unwrap_err().found, "i32");
+    /// ```
+    fn check(object: &Object) -> Result<(), TypeConflict> {
+        match object.get(Self::NAME) {
+            Some(value) if !(**value).as_any().is::<Self::Value>() => Err(TypeConflict {
+                key: Self::NAME.to_string(),
+                expected: any::type_name::<Self::Value>(),
+                found: (**value).dyn_type_name(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An `Object` restricted to the keys of the schema `S`, each holding a value of its declared
+/// type.
+///
+/// Values are accessed through the key marker types of the schema, so a misspelled key or a value
+/// of the wrong type is a compile error. The values are still stored in a plain `Object`, which
+/// can be borrowed through `Deref` or taken out with [`TypedObject::into_inner`].
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{typed_object, TypedObject};
+///
+/// typed_object! {
+///     /// The server configuration.
+///     pub schema Server {
+///         /// The host name.
+///         Host: "host" => String,
+///         /// The TCP port.
+///         Port: "port" => u16,
+///     }
+/// }
+///
+/// let mut server = TypedObject::<Server>::new();
+/// server.set::<Host>("localhost".to_string());
+/// server.set::<Port>(8080);
+/// *server.get_mut::<Port>().unwrap() += 1;
+/// assert_eq!(server.get::<Port>(), Some(&8081));
+/// assert_eq!(server.get_as::<String>("host").unwrap(), "localhost");
+/// ```
+///
+/// Storing a value of the wrong type doesn't compile:
+///
+/// ```compile_fail
+/// use dynamic_object::{typed_object, TypedObject};
+///
+/// typed_object! {
+///     pub schema Server {
+///         Port: "port" => u16,
+///     }
+/// }
+///
+/// let mut server = TypedObject::<Server>::new();
+/// server.set::<Port>("8080");
+/// ```
+pub struct TypedObject<S> {
+    object: Object,
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S: Schema> TypedObject<S> {
+    /// Creates an empty `TypedObject`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Empty {}
+    /// }
+    ///
+    /// assert!(TypedObject::<Empty>::new().is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            object: Object::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Wraps `object`, checking that every key of the schema it holds has a value of the declared
+    /// type.
+    ///
+    /// Keys of the schema may be absent, and keys outside the schema are kept but can only be read
+    /// through the underlying `Object`. A [sealed](Object::seal) `object` is rejected with
+    /// [`SchemaError::Sealed`], since absent keys of the schema couldn't be set on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Server {
+    ///         Port: "port" => u16,
+    ///     }
+    /// }
+    ///
+    /// let server = TypedObject::<Server>::from_object(object!({ port: 80u16, })).unwrap();
+    /// assert_eq!(server.get::<Port>(), Some(&80));
+    /// assert!(TypedObject::<Server>::from_object(object!({ port: "80", })).is_err());
+    /// ```
+    pub fn from_object(object: Object) -> Result<Self, SchemaError> {
+        if object.is_sealed() {
+            return Err(SchemaError::Sealed);
+        }
+        S::validate(&object)?;
+        Ok(Self {
+            object,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns the value of the key `K`, or `None` if it is absent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Server {
+    ///         Port: "port" => u16,
+    ///     }
+    /// }
+    ///
+    /// let mut server = TypedObject::<Server>::new();
+    /// assert_eq!(server.get::<Port>(), None);
+    /// server.set::<Port>(80);
+    /// assert_eq!(server.get::<Port>(), Some(&80));
+    /// ```
+    pub fn get<K: Key<S>>(&self) -> Option<&K::Value> {
+        self.object.get_as(K::NAME)
+    }
+
+    /// Returns a mutable reference to the value of the key `K`, or `None` if it is absent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Stats {
+    ///         Hits: "hits" => u64,
+    ///     }
+    /// }
+    ///
+    /// let mut stats = TypedObject::<Stats>::new();
+    /// stats.set::<Hits>(1);
+    /// *stats.get_mut::<Hits>().unwrap() += 1;
+    /// assert_eq!(stats.get::<Hits>(), Some(&2));
+    /// ```
+    pub fn get_mut<K: Key<S>>(&mut self) -> Option<&mut K::Value> {
+        self.object.get_mut_as(K::NAME)
+    }
+
+    /// Sets the value of the key `K`, returning the previous one if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Server {
+    ///         Port: "port" => u16,
+    ///     }
+    /// }
+    ///
+    /// let mut server = TypedObject::<Server>::new();
+    /// assert_eq!(server.set::<Port>(80), None);
+    /// assert_eq!(server.set::<Port>(8080), Some(80));
+    /// ```
+    pub fn set<K: Key<S>>(&mut self, value: K::Value) -> Option<K::Value> {
+        match self.object.map.get_mut(K::NAME) {
+            Some(slot) => downcast::<K::Value>(std::mem::replace(slot, Box::new(value))),
+            None => {
+                self.object.insert(K::NAME, value);
+                None
+            }
+        }
+    }
+
+    /// Removes the key `K`, returning its value if it was present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Server {
+    ///         Port: "port" => u16,
+    ///     }
+    /// }
+    ///
+    /// let mut server = TypedObject::<Server>::new();
+    /// server.set::<Port>(80);
+    /// assert_eq!(server.remove::<Port>(), Some(80));
+    /// assert_eq!(server.remove::<Port>(), None);
+    /// ```
+    pub fn remove<K: Key<S>>(&mut self) -> Option<K::Value> {
+        self.object.remove(K::NAME).and_then(downcast::<K::Value>)
+    }
+
+    /// Consumes the `TypedObject`, returning the underlying `Object`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::{object, typed_object, TypedObject};
+    ///
+    /// typed_object! {
+    ///     schema Server {
+    ///         Port: "port" => u16,
+    ///     }
+    /// }
+    ///
+    /// let mut server = TypedObject::<Server>::new();
+    /// server.set::<Port>(80);
+    /// assert_eq!(server.into_inner(), object!({ port: 80u16, }));
+    /// ```
+    pub fn into_inner(self) -> Object {
+        self.object
+    }
+}
+
+/// Unboxes a value of a schema key, or returns `None` if it isn't of the type of its key.
+fn downcast<T: 'static>(value: Box<dyn AnyType>) -> Option<T> {
+    AnyType::into_any(value).downcast().ok().map(|value| *value)
+}
+
+/// Items used by [`typed_object!`](crate::typed_object), which are not part of the public API.
+#[doc(hidden)]
+pub mod private {
+    /// The supertrait sealing [`Key`](crate::Key).
+    pub trait Sealed<S> {}
+
+    /// Panics if two of `names` are equal, failing compilation when evaluated in a constant.
+    pub const fn assert_unique_names(names: &[&str]) {
+        let mut i = 0;
+        while i < names.len() {
+            let mut j = i + 1;
+            while j < names.len() {
+                if str_eq(names[i], names[j]) {
+                    panic!("two keys of the schema have the same name");
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+    }
+
+    const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+}
+
+impl<S: Schema> Default for TypedObject<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> fmt::Debug for TypedObject<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TypedObject").field(&self.object).finish()
+    }
+}
+
+impl<S> Deref for TypedObject<S> {
+    type Target = Object;
+
+    fn deref(&self) -> &Self::Target {
+        &self.object
+    }
+}
+
+impl<S> From<TypedObject<S>> for Object {
+    fn from(typed: TypedObject<S>) -> Self {
+        typed.object
+    }
+}
+
+/// Declares a [`Schema`] and its [`Key`] marker types for use with [`TypedObject`].
+///
+/// Each key is written as `Marker: "name" => Type`, where `Marker` is the name of the generated
+/// unit struct, `"name"` the key it is stored under and `Type` the type of its value. The schema
+/// itself is generated as an uninhabited enum, since it is only ever used as a type parameter.
+/// Attributes, including doc comments, are copied onto the generated types. Two keys with the
+/// same name are rejected at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use dynamic_object::{typed_object, TypedObject};
+///
+/// typed_object! {
+///     /// A user record.
+///     pub schema User {
+///         /// The display name.
+///         Name: "name" => String,
+///         Admin: "is_admin" => bool,
+///     }
+/// }
+///
+/// let mut user = TypedObject::<User>::new();
+/// user.set::<Name>("Ada".to_string());
+/// user.set::<Admin>(true);
+/// assert_eq!(user.get_as::<bool>("is_admin"), Some(&true));
+/// ```
+///
+/// ```compile_fail
+/// use dynamic_object::typed_object;
+///
+/// typed_object! {
+///     pub schema Clash {
+///         A: "x" => i32,
+///         B: "x" => String,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! typed_object {
+    (
+        $(#[$meta:meta])*
+        $vis:vis schema $schema:ident {
+            $(
+                $(#[$key_meta:meta])*
+                $key:ident: $name:literal => $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        $vis enum $schema {}
+
+        impl $crate::Schema for $schema {
+            fn validate(
+                #[allow(unused_variables)] object: &$crate::Object,
+            ) -> ::std::result::Result<(), $crate::TypeConflict> {
+                $(<$key as $crate::Key<$schema>>::check(object)?;)*
+                ::std::result::Result::Ok(())
+            }
+        }
+
+        const _: () = $crate::__private::assert_unique_names(&[$($name),*]);
+
+        $(
+            $(#[$key_meta])*
+            #[derive(Debug, Clone, Copy)]
+            $vis struct $key;
+
+            impl $crate::__private::Sealed<$schema> for $key {}
+
+            impl $crate::Key<$schema> for $key {
+                const NAME: &'static str = $name;
+                type Value = $ty;
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    typed_object! {
+        schema Point {
+            X: "x" => i32,
+            Y: "y" => i32,
+            Label: "label" => &'static str,
+        }
+    }
+
+    #[test]
+    fn typed_object_validates_and_accesses_keys() {
+        let mut point = TypedObject::<Point>::from_object(object!({ x: 1, extra: true, })).unwrap();
+        point.set::<Y>(2);
+        *point.get_mut::<X>().unwrap() += 10;
+        assert_eq!((point.get::<X>(), point.get::<Y>()), (Some(&11), Some(&2)));
+        assert_eq!(point.get::<Label>(), None);
+        assert_eq!(point.get_as::<bool>("extra"), Some(&true));
+
+        let conflict = TypedObject::<Point>::from_object(object!({ y: 1, label: 2, })).unwrap_err();
+        assert_eq!(
+            conflict,
+            SchemaError::TypeConflict(TypeConflict {
+                key: "label".to_string(),
+                expected: "&str",
+                found: "i32",
+            })
+        );
+        assert_eq!(Object::from(point), object!({ extra: true, x: 11, y: 2, }));
+    }
+
+    #[test]
+    fn typed_object_rejects_sealed_objects() {
+        let mut sealed = object!({ x: 1 });
+        sealed.seal();
+        assert_eq!(
+            TypedObject::<Point>::from_object(sealed).unwrap_err(),
+            SchemaError::Sealed
+        );
+        assert_eq!(SchemaError::Sealed.to_string(), "object is sealed");
+    }
+}
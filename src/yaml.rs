@@ -0,0 +1,267 @@
+//! Conversions between `Object` and YAML values.
+
+use serde_yaml::{Mapping, Number, Value};
+
+use crate::{AnyType, ConversionError, Null, Object};
+
+impl Object {
+    /// Converts a YAML mapping into an `Object`.
+    ///
+    /// Mappings become nested `Object`s and sequences become `Vec<Box<dyn AnyType>>`. Leaves are stored
+    /// as `String`, `bool`, `i64`, `u64` (for integers above `i64::MAX`), `f64`, or [`Null`] for `null`.
+    /// Tags are discarded and the tagged value is converted as if it were untagged.
+    ///
+    /// Boolean and numeric mapping keys are stringified. If that makes two keys of a mapping equal,
+    /// such as `1` and `"1"`, `ConversionError::DuplicateKey` is returned. Any other non-string key
+    /// results in `ConversionError::UnsupportedKey`.
+    ///
+    /// This method requires the `yaml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::Object;
+    /// let mapping: serde_yaml::Mapping = serde_yaml::from_str("port: 8080\ndb:\n  user: admin").unwrap();
+    /// let config = Object::from_yaml(mapping).unwrap();
+    /// assert_eq!(config.get_as::<i64>("port"), Some(&8080));
+    /// ```
+    pub fn from_yaml(mapping: Mapping) -> Result<Self, ConversionError> {
+        from_yaml_mapping(mapping, &mut Vec::new())
+    }
+
+    /// Converts the `Object` into a YAML mapping.
+    ///
+    /// Besides the types produced by [`Object::from_yaml`], `&str`, `char` and every integer and
+    /// float primitive are accepted. 128-bit integers that don't fit in an `i64` or `u64` result in
+    /// `ConversionError::OutOfRange`, and any other value in `ConversionError::Unsupported`.
+    ///
+    /// This method requires the `yaml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dynamic_object::object;
+    /// let config = object!({
+    ///     port: 8080,
+    ///     db: {
+    ///         user: "admin",
+    ///     },
+    /// });
+    /// let mapping = config.to_yaml().unwrap();
+    /// assert_eq!(mapping["db"]["user"].as_str(), Some("admin"));
+    /// ```
+    pub fn to_yaml(&self) -> Result<Mapping, ConversionError> {
+        to_yaml_mapping(self, &mut Vec::new())
+    }
+}
+
+fn from_yaml_mapping(mapping: Mapping, path: &mut Vec<String>) -> Result<Object, ConversionError> {
+    let mut object = Object::new();
+    for (key, value) in mapping {
+        let key = match key {
+            Value::String(s) => s,
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => n.to_string(),
+            _ => {
+                return Err(ConversionError::UnsupportedKey {
+                    path: path.join("."),
+                })
+            }
+        };
+        let duplicate = object.map.contains_key(&key);
+        path.push(key);
+        if duplicate {
+            return Err(ConversionError::DuplicateKey {
+                path: path.join("."),
+            });
+        }
+        let value = from_yaml_value(value, path)?;
+        let key = path.pop().expect("the key was just pushed");
+        object.map.insert(key, value);
+    }
+    Ok(object)
+}
+
+fn from_yaml_value(
+    value: Value,
+    path: &mut Vec<String>,
+) -> Result<Box<dyn AnyType>, ConversionError> {
+    Ok(match value {
+        Value::Null => Box::new(Null),
+        Value::Bool(b) => Box::new(b),
+        Value::Number(n) => {
+            if let Some(n) = n.as_i64() {
+                Box::new(n)
+            } else if let Some(n) = n.as_u64() {
+                Box::new(n)
+            } else {
+                Box::new(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        Value::String(s) => Box::new(s),
+        Value::Sequence(sequence) => {
+            let mut values = Vec::<Box<dyn AnyType>>::with_capacity(sequence.len());
+            for (i, value) in sequence.into_iter().enumerate() {
+                path.push(i.to_string());
+                values.push(from_yaml_value(value, path)?);
+                path.pop();
+            }
+            Box::new(values)
+        }
+        Value::Mapping(mapping) => Box::new(from_yaml_mapping(mapping, path)?),
+        Value::Tagged(tagged) => from_yaml_value(tagged.value, path)?,
+    })
+}
+
+fn to_yaml_mapping(object: &Object, path: &mut Vec<String>) -> Result<Mapping, ConversionError> {
+    let mut mapping = Mapping::with_capacity(object.map.len());
+    for (key, value) in &object.map {
+        path.push(key.clone());
+        mapping.insert(Value::String(key.clone()), to_yaml_value(&**value, path)?);
+        path.pop();
+    }
+    Ok(mapping)
+}
+
+macro_rules! convert_numbers {
+    ($value:ident, $($t:ty),*) => {
+        $(
+            if let Some(n) = $value.downcast_ref::<$t>() {
+                return Ok(Value::Number(Number::from(*n)));
+            }
+        )*
+    };
+}
+
+fn to_yaml_value(value: &dyn AnyType, path: &mut Vec<String>) -> Result<Value, ConversionError> {
+    if let Some(object) = value.downcast_ref::<Object>() {
+        return to_yaml_mapping(object, path).map(Value::Mapping);
+    }
+    if let Some(array) = value.downcast_ref::<Vec<Box<dyn AnyType>>>() {
+        let mut values = Vec::with_capacity(array.len());
+        for (i, value) in array.iter().enumerate() {
+            path.push(i.to_string());
+            values.push(to_yaml_value(&**value, path)?);
+            path.pop();
+        }
+        return Ok(Value::Sequence(values));
+    }
+    if value.as_any().is::<Null>() || value.as_any().is::<()>() {
+        return Ok(Value::Null);
+    }
+    if let Some(s) = value.downcast_ref::<String>() {
+        return Ok(Value::String(s.clone()));
+    }
+    if let Some(s) = value.downcast_ref::<&str>() {
+        return Ok(Value::String(s.to_string()));
+    }
+    if let Some(c) = value.downcast_ref::<char>() {
+        return Ok(Value::String(c.to_string()));
+    }
+    if let Some(b) = value.downcast_ref::<bool>() {
+        return Ok(Value::Bool(*b));
+    }
+    convert_numbers!(value, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+    if let Some(n) = value.downcast_ref::<i128>() {
+        return i64::try_from(*n)
+            .map(Number::from)
+            .or_else(|_| u64::try_from(*n).map(Number::from))
+            .map(Value::Number)
+            .map_err(|_| ConversionError::OutOfRange {
+                path: path.join("."),
+            });
+    }
+    if let Some(n) = value.downcast_ref::<u128>() {
+        return u64::try_from(*n)
+            .map(|n| Value::Number(Number::from(n)))
+            .map_err(|_| ConversionError::OutOfRange {
+                path: path.join("."),
+            });
+    }
+    Err(ConversionError::Unsupported {
+        path: path.join("."),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object;
+
+    use super::*;
+
+    const CONFIG: &str = "
+title: example
+empty: null
+server:
+  ports: [8000, 8001]
+  ratio: 0.5
+  enabled: true
+users:
+  - name: a
+  - name: b
+";
+
+    #[test]
+    fn round_trips_yaml_mappings() {
+        let mapping: Mapping = serde_yaml::from_str(CONFIG).unwrap();
+        let object = Object::from_yaml(mapping.clone()).unwrap();
+
+        assert_eq!(
+            object.get_as::<String>("title"),
+            Some(&"example".to_string())
+        );
+        assert_eq!(object.get_as::<Null>("empty"), Some(&Null));
+        let server = object.get_as::<Object>("server").unwrap();
+        assert_eq!(server.get_as::<f64>("ratio"), Some(&0.5));
+        assert_eq!(server.get_as::<bool>("enabled"), Some(&true));
+        let ports = server.get_as::<Vec<Box<dyn AnyType>>>("ports").unwrap();
+        assert_eq!(ports[0].downcast_ref::<i64>(), Some(&8000));
+        let users = object.get_as::<Vec<Box<dyn AnyType>>>("users").unwrap();
+        let user = users[1].downcast_ref::<Object>().unwrap();
+        assert_eq!(user.get_as::<String>("name"), Some(&"b".to_string()));
+
+        assert_eq!(object.to_yaml(), Ok(mapping));
+    }
+
+    #[test]
+    fn handles_non_string_keys() {
+        let mapping: Mapping = serde_yaml::from_str("1: one\ntrue: yes").unwrap();
+        let object = Object::from_yaml(mapping).unwrap();
+        assert_eq!(object.get_as::<String>("1"), Some(&"one".to_string()));
+        assert_eq!(object.get_as::<String>("true"), Some(&"yes".to_string()));
+
+        let mapping: Mapping = serde_yaml::from_str("outer:\n  [1, 2]: pair").unwrap();
+        assert_eq!(
+            Object::from_yaml(mapping).unwrap_err(),
+            ConversionError::UnsupportedKey {
+                path: "outer".to_string()
+            }
+        );
+
+        let mapping: Mapping = serde_yaml::from_str("1: a\n'1': b").unwrap();
+        assert_eq!(
+            Object::from_yaml(mapping).unwrap_err(),
+            ConversionError::DuplicateKey {
+                path: "1".to_string()
+            }
+        );
+        let mapping: Mapping = serde_yaml::from_str("outer:\n  'true': a\n  true: b").unwrap();
+        assert_eq!(
+            Object::from_yaml(mapping).unwrap_err().to_string(),
+            "more than one mapping key turns into `outer.true`"
+        );
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        let object = object!({
+            big: u128::MAX,
+        });
+        assert_eq!(
+            object.to_yaml(),
+            Err(ConversionError::OutOfRange {
+                path: "big".to_string()
+            })
+        );
+    }
+}